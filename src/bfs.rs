@@ -12,8 +12,7 @@ pub(crate) fn bfs<T: Clone + Eq + Ord + Hash, F: FnMut(usize, usize, Option<usiz
         .filter(|i| **i < graph.values.len())
         .map(|i| (0, *i, None))
         .collect();
-    loop {
-        let Some(x) = queue.pop_front() else { break; };
+    while let Some(x) = queue.pop_front() {
         if !f(x.0, x.1, x.2) {
             continue;
         }
@@ -32,8 +31,7 @@ pub(crate) fn bfs_path<T: Clone + Eq + Ord + Hash, F: FnMut(&[usize]) -> bool>(
         .filter(|i| **i < graph.values.len())
         .map(|i| (vec![], *i))
         .collect();
-    loop {
-        let Some(x) = queue.pop_front() else { break; };
+    while let Some(x) = queue.pop_front() {
         let mut new_path = x.0.clone();
         new_path.push(x.1);
         if !f(new_path.as_slice()) {
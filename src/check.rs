@@ -0,0 +1,210 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{Context as _, Result};
+use itertools::Itertools;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    dfs::dfs,
+    graph::Graph,
+    scc::{find_cycles, longest_path},
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ForbiddenRule {
+    from: String,
+    to: String,
+    #[serde(default)]
+    transitive: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LayerRule {
+    pub(crate) name: String,
+    pub(crate) pattern: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Rules {
+    #[serde(default)]
+    forbidden: Vec<ForbiddenRule>,
+    #[serde(default)]
+    pub(crate) layers: Vec<LayerRule>,
+    #[serde(default)]
+    no_cycles: bool,
+    max_depth: Option<usize>,
+    max_fan_out: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Violation {
+    pub(crate) rule: &'static str,
+    pub(crate) message: String,
+    pub(crate) path: Vec<usize>,
+}
+
+pub(crate) const RULE_IDS: &[&str] = &[
+    "forbidden",
+    "layers",
+    "no-cycles",
+    "max-depth",
+    "max-fan-out",
+];
+
+fn full_match(pattern: &str) -> Result<Regex> {
+    Regex::new(&format!("^(?:{})$", pattern)).with_context(|| format!("bad pattern: {}", pattern))
+}
+
+fn find_path(
+    graph: &Graph<String>,
+    start: usize,
+    mut is_goal: impl FnMut(usize) -> bool,
+) -> Option<Vec<usize>> {
+    let mut prev: HashMap<usize, usize> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(n) = queue.pop_front() {
+        for &t in graph.deps.get(&n).into_iter().flatten() {
+            if is_goal(t) {
+                let mut path = vec![t, n];
+                let mut m = n;
+                while m != start {
+                    m = prev[&m];
+                    path.push(m);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if t == start || prev.contains_key(&t) {
+                continue;
+            }
+            prev.insert(t, n);
+            queue.push_back(t);
+        }
+    }
+    None
+}
+
+pub(crate) fn assign_layers(
+    graph: &Graph<String>,
+    layers: &[LayerRule],
+) -> Result<Vec<Option<usize>>> {
+    let patterns = layers
+        .iter()
+        .map(|l| full_match(&l.pattern))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(graph
+        .values
+        .iter()
+        .map(|v| patterns.iter().position(|p| p.is_match(v)))
+        .collect())
+}
+
+pub(crate) fn layer_violations(
+    graph: &Graph<String>,
+    layers: &[LayerRule],
+) -> Result<Vec<Violation>> {
+    let assigned = assign_layers(graph, layers)?;
+    let mut violations = vec![];
+    let mut visited = vec![false; graph.values.len()];
+    let mut path: Vec<usize> = vec![];
+    let starts = graph
+        .find_roots()
+        .into_iter()
+        .chain(0..graph.values.len())
+        .collect_vec();
+    for start in starts {
+        dfs(graph, &[start], |i, t, f| {
+            path.truncate(i);
+            path.push(t);
+            if let Some(f) = f {
+                if let (Some(from), Some(to)) = (assigned[f], assigned[t]) {
+                    if to > from {
+                        violations.push(Violation {
+                            rule: "layers",
+                            message: format!(
+                                "{} must not depend on {}",
+                                layers[from].name, layers[to].name
+                            ),
+                            path: path.clone(),
+                        });
+                    }
+                }
+            }
+            !std::mem::replace(&mut visited[t], true)
+        });
+    }
+    Ok(violations)
+}
+
+pub(crate) fn check(graph: &Graph<String>, rules: &Rules) -> Result<Vec<Violation>> {
+    let mut violations = vec![];
+    for rule in rules.forbidden.iter() {
+        let (from, to) = (full_match(&rule.from)?, full_match(&rule.to)?);
+        for (i, v) in graph.values.iter().enumerate() {
+            if !from.is_match(v) {
+                continue;
+            }
+            let paths: Vec<Vec<usize>> = if rule.transitive {
+                find_path(graph, i, |t| to.is_match(&graph.values[t]))
+                    .into_iter()
+                    .collect()
+            } else {
+                graph
+                    .deps
+                    .get(&i)
+                    .into_iter()
+                    .flatten()
+                    .filter(|t| to.is_match(&graph.values[**t]))
+                    .map(|t| vec![i, *t])
+                    .collect()
+            };
+            for path in paths {
+                violations.push(Violation {
+                    rule: "forbidden",
+                    message: format!("{} must not depend on {}", rule.from, rule.to),
+                    path,
+                });
+            }
+        }
+    }
+    violations.extend(layer_violations(graph, &rules.layers)?);
+    if rules.no_cycles {
+        for c in find_cycles(graph) {
+            let path = find_path(graph, c[0], |t| t == c[0]).unwrap_or_default();
+            violations.push(Violation {
+                rule: "no-cycles",
+                message: "contains a cycle".to_owned(),
+                path,
+            });
+        }
+    }
+    if let Some(max_depth) = rules.max_depth {
+        // cycles count as a single level
+        let path = longest_path(graph);
+        let depth = path.len().saturating_sub(1);
+        if depth > max_depth {
+            violations.push(Violation {
+                rule: "max-depth",
+                message: format!("depth {} exceeds {}", depth, max_depth),
+                path,
+            });
+        }
+    }
+    if let Some(max_fan_out) = rules.max_fan_out {
+        for (i, _) in graph.values.iter().enumerate() {
+            let fan_out = graph.deps.get(&i).map_or(0, |vs| vs.len());
+            if fan_out > max_fan_out {
+                violations.push(Violation {
+                    rule: "max-fan-out",
+                    message: format!("fan-out {} exceeds {}", fan_out, max_fan_out),
+                    path: vec![i],
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
@@ -0,0 +1,85 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    consts::KIND_ATTR,
+    graph::{Attrs, Edge, Graph},
+};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cluster {
+    pub(crate) children: BTreeMap<String, Cluster>,
+    pub(crate) nodes: Vec<usize>,
+}
+
+pub(crate) fn cluster_path<'a>(name: &'a str, separator: &str, depth: usize) -> Vec<&'a str> {
+    let mut parts: Vec<&str> = name.split(separator).collect();
+    parts.pop();
+    parts.truncate(depth);
+    parts
+}
+
+pub(crate) fn clusters(graph: &Graph<String>, separator: &str, depth: usize) -> Cluster {
+    let mut root = Cluster::default();
+    for (i, v) in graph.values.iter().enumerate() {
+        let mut cluster = &mut root;
+        for part in cluster_path(v, separator, depth) {
+            cluster = cluster.children.entry(part.to_owned()).or_default();
+        }
+        cluster.nodes.push(i);
+    }
+    root
+}
+
+// replaces the nodes under each cluster at `depth` with a single node named after the cluster
+// with a trailing separator, so it can't collide with a node of the same name outside the cluster;
+// edges between them are merged per kind and labeled with their count when more than one
+pub(crate) fn collapse(graph: &Graph<String>, separator: &str, depth: usize) -> Graph<String> {
+    let mut collapsed = Graph::default();
+    let mut keys = vec![];
+    for (i, v) in graph.values.iter().enumerate() {
+        let path = cluster_path(v, separator, depth);
+        if path.len() == depth {
+            let name = path.join(separator);
+            let j = collapsed.add_node(&format!("{}{}", name, separator));
+            collapsed.add_node_attrs(
+                j,
+                Attrs::from([
+                    ("label".to_owned(), name),
+                    ("shape".to_owned(), "folder".to_owned()),
+                ]),
+            );
+            keys.push(j);
+        } else {
+            let j = collapsed.add_node(v);
+            collapsed.add_node_attrs(j, graph.node_attrs(i).cloned().unwrap_or_default());
+            keys.push(j);
+        }
+    }
+    let mut counts: HashMap<(usize, usize, Option<&String>), usize> = HashMap::new();
+    let mut edges = vec![];
+    for (e, attrs) in graph.to_index_edges_with_attrs() {
+        let (from, to) = (keys[e.0], keys[e.1]);
+        if from == to {
+            continue;
+        }
+        let key = (from, to, attrs.get(KIND_ATTR));
+        *counts.entry(key).or_insert_with(|| {
+            edges.push(key);
+            0
+        }) += 1;
+    }
+    for key in edges {
+        let (from, to, kind) = key;
+        let mut attrs = Attrs::new();
+        if let Some(kind) = kind {
+            attrs.insert(KIND_ATTR.to_owned(), kind.clone());
+        }
+        let count = counts[&key];
+        if count > 1 {
+            attrs.insert("label".to_owned(), count.to_string());
+        }
+        let e = Edge(collapsed.values[from].clone(), collapsed.values[to].clone());
+        collapsed.add_edge_with_attrs(&e, attrs);
+    }
+    collapsed
+}
@@ -1 +1,20 @@
 pub(crate) const DEFAULT_MAX_DEPTH: usize = 1024;
+pub(crate) const KIND_ATTR: &str = "kind";
+pub(crate) const WEIGHT_ATTR: &str = "weight";
+pub(crate) const COMPONENT_ATTR: &str = "component";
+pub(crate) const MEMBERS_ATTR: &str = "members";
+pub(crate) const CONDENSED_SEPARATOR: &str = "+";
+pub(crate) const SVG_CHAR_WIDTH: f64 = 7.0;
+pub(crate) const SVG_NODE_HEIGHT: f64 = 28.0;
+pub(crate) const DEFAULT_EDGE_KIND: &str = "normal";
+// attributes passed to DOT as they are; the others are prefixed so they can't affect the rendering
+pub(crate) const DOT_ATTRS: &[&str] = &["label", "shape"];
+pub(crate) const DOT_ATTR_PREFIX: &str = "depq_";
+pub(crate) const DEFAULT_DOT_KIND_STYLES: &[(&str, &str)] = &[
+    ("dev", "style=dashed"),
+    ("build", "style=dotted"),
+    ("optional", "style=dashed, color=gray"),
+    ("peer", "style=bold"),
+    ("implicit", "style=dashed"),
+    ("order-only", "style=dotted"),
+];
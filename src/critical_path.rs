@@ -0,0 +1,57 @@
+use std::fmt::Debug;
+use std::{collections::HashSet, hash::Hash};
+
+use crate::graph::Graph;
+use crate::tsort::tsort;
+
+#[derive(Debug, Clone)]
+pub(crate) struct CriticalPath {
+    pub(crate) path: Vec<usize>,
+    pub(crate) cost: f64,
+    pub(crate) slacks: Vec<(usize, f64)>,
+}
+
+pub(crate) fn critical_path<T: Clone + Debug + Eq + Ord + Hash>(
+    graph: &Graph<T>,
+    weights: &[f64],
+) -> Result<CriticalPath, HashSet<usize>> {
+    let mut order = vec![];
+    tsort(graph, |n| order.push(n))?;
+    // the longest chain starting from each node down to a leaf
+    let mut down = vec![0.0; graph.values.len()];
+    for &n in order.iter().rev() {
+        let deps = graph.deps.get(&n).into_iter().flatten();
+        down[n] = weights[n] + deps.map(|&d| down[d]).fold(0.0, f64::max);
+    }
+    // the longest chain ending at each node from a root
+    let mut up = vec![0.0; graph.values.len()];
+    for &n in order.iter() {
+        up[n] += weights[n];
+        for &d in graph.deps.get(&n).into_iter().flatten() {
+            up[d] = f64::max(up[d], up[n]);
+        }
+    }
+    let roots = graph.find_roots();
+    let mut path = vec![];
+    let mut next = order
+        .iter()
+        .copied()
+        .filter(|n| roots.contains(n))
+        .reduce(|a, b| if down[b] > down[a] { b } else { a });
+    let cost = next.map_or(0.0, |n| down[n]);
+    while let Some(n) = next {
+        path.push(n);
+        next = graph
+            .deps
+            .get(&n)
+            .into_iter()
+            .flatten()
+            .copied()
+            .reduce(|a, b| if down[b] > down[a] { b } else { a });
+    }
+    let slacks = order
+        .iter()
+        .map(|&n| (n, cost - (up[n] + down[n] - weights[n])))
+        .collect();
+    Ok(CriticalPath { path, cost, slacks })
+}
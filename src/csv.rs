@@ -0,0 +1,71 @@
+use std::io::{BufRead, Write};
+
+use anyhow::{bail, Result};
+
+pub(crate) fn read_records<R: BufRead>(mut r: R, delimiter: char) -> Result<Vec<Vec<String>>> {
+    let mut input = String::new();
+    r.read_to_string(&mut input)?;
+    let mut records = vec![];
+    let mut record: Vec<String> = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut cs = input.chars().peekable();
+    while let Some(c) = cs.next() {
+        if quoted {
+            match c {
+                '"' if cs.peek() == Some(&'"') => {
+                    cs.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' if cs.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        bail!("unterminated quoted field");
+    }
+    if !record.is_empty() || !field.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn quote(s: &str, delimiter: char) -> String {
+    if s.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+pub(crate) fn write_record<W: Write, S: AsRef<str>>(
+    mut w: W,
+    record: &[S],
+    delimiter: char,
+) -> Result<()> {
+    let line = record
+        .iter()
+        .map(|s| quote(s.as_ref(), delimiter))
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string());
+    w.write_all(line.as_bytes())?;
+    w.write_all(b"\n")?;
+    Ok(())
+}
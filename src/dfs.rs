@@ -13,8 +13,7 @@ pub(crate) fn dfs<T: Clone + Eq + Ord + Hash, F: FnMut(usize, usize, Option<usiz
         .map(|i| (0, *i, None))
         .collect();
     stack.reverse();
-    loop {
-        let Some(x) = stack.pop() else { break; };
+    while let Some(x) = stack.pop() {
         if !f(x.0, x.1, x.2) {
             continue;
         }
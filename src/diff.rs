@@ -0,0 +1,101 @@
+use std::{collections::HashSet, hash::Hash};
+
+use crate::{
+    graph::{Edge, Graph},
+    scc::find_cycles,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct GraphDiff<T: Clone> {
+    pub(crate) removed_nodes: Vec<T>,
+    pub(crate) added_nodes: Vec<T>,
+    pub(crate) removed_edges: Vec<Edge<T>>,
+    pub(crate) added_edges: Vec<Edge<T>>,
+    pub(crate) new_transitive: Vec<(T, Vec<T>)>,
+    pub(crate) new_cycles: Vec<Vec<T>>,
+}
+
+impl<T: Clone> GraphDiff<T> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.removed_nodes.is_empty()
+            && self.added_nodes.is_empty()
+            && self.removed_edges.is_empty()
+            && self.added_edges.is_empty()
+            && self.new_transitive.is_empty()
+            && self.new_cycles.is_empty()
+    }
+}
+
+fn edge_set<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> HashSet<(T, T)> {
+    graph.to_edges().into_iter().map(|e| (e.0, e.1)).collect()
+}
+
+fn edges_minus<T: Clone + Eq + Ord + Hash>(a: &Graph<T>, b: &HashSet<(T, T)>) -> Vec<Edge<T>> {
+    let mut seen = HashSet::new();
+    a.to_edges()
+        .into_iter()
+        .filter(|e| !b.contains(&(e.0.clone(), e.1.clone())))
+        .filter(|e| seen.insert((e.0.clone(), e.1.clone())))
+        .collect()
+}
+
+fn cycle_values<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<Vec<T>> {
+    find_cycles(graph)
+        .iter()
+        .map(|c| {
+            let mut c: Vec<T> = c.iter().map(|i| graph.values[*i].clone()).collect();
+            c.sort();
+            c
+        })
+        .collect()
+}
+
+pub(crate) fn diff<T: Clone + Eq + Ord + Hash>(old: &Graph<T>, new: &Graph<T>) -> GraphDiff<T> {
+    let removed_nodes = old
+        .values
+        .iter()
+        .filter(|v| !new.value_to_index.contains_key(v))
+        .cloned()
+        .collect();
+    let added_nodes = new
+        .values
+        .iter()
+        .filter(|v| !old.value_to_index.contains_key(v))
+        .cloned()
+        .collect();
+    let removed_edges = edges_minus(old, &edge_set(new));
+    let added_edges = edges_minus(new, &edge_set(old));
+    let mut new_transitive = vec![];
+    for root in new.find_roots() {
+        let old_reachable: HashSet<&T> = match old.value_to_index.get(&new.values[root]) {
+            Some(&i) => old.reachable(i).iter().map(|j| &old.values[*j]).collect(),
+            None => HashSet::new(),
+        };
+        let mut introduced: Vec<usize> = new
+            .reachable(root)
+            .into_iter()
+            .filter(|j| !old_reachable.contains(&new.values[*j]))
+            .collect();
+        if introduced.is_empty() {
+            continue;
+        }
+        introduced.sort();
+        new_transitive.push((
+            new.values[root].clone(),
+            introduced.iter().map(|j| new.values[*j].clone()).collect(),
+        ));
+    }
+    let old_cycles: HashSet<Vec<T>> = cycle_values(old).into_iter().collect();
+    let new_cycles = cycle_values(new)
+        .into_iter()
+        .filter(|c| !old_cycles.contains(c))
+        .collect();
+    GraphDiff {
+        removed_nodes,
+        added_nodes,
+        removed_edges,
+        added_edges,
+        new_transitive,
+        new_cycles,
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    hash::Hash,
+};
+
+use crate::graph::Graph;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct State(f64, usize);
+
+impl Eq for State {}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+pub(crate) fn shortest_path<T: Clone + Eq + Ord + Hash, F: FnMut(usize, usize) -> f64>(
+    graph: &Graph<T>,
+    start: usize,
+    end: usize,
+    mut weight: F,
+) -> Option<(Vec<usize>, f64)> {
+    let mut dist: Vec<Option<f64>> = vec![None; graph.values.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; graph.values.len()];
+    let mut heap = BinaryHeap::new();
+    dist[start] = Some(0.0);
+    heap.push(Reverse(State(0.0, start)));
+    while let Some(Reverse(State(d, n))) = heap.pop() {
+        if n == end {
+            break;
+        }
+        if dist[n].is_some_and(|x| d > x) {
+            continue;
+        }
+        for &t in graph.deps.get(&n).into_iter().flatten() {
+            let nd = d + weight(n, t);
+            if dist[t].is_none_or(|x| nd < x) {
+                dist[t] = Some(nd);
+                prev[t] = Some(n);
+                heap.push(Reverse(State(nd, t)));
+            }
+        }
+    }
+    let cost = dist[end]?;
+    let mut path = vec![end];
+    let mut n = end;
+    while let Some(p) = prev[n] {
+        path.push(p);
+        n = p;
+    }
+    path.reverse();
+    Some((path, cost))
+}
@@ -0,0 +1,72 @@
+use std::hash::Hash;
+
+use crate::graph::Graph;
+
+// Cooper, Harvey and Kennedy, "A Simple, Fast Dominance Algorithm"
+pub(crate) fn dominators<T: Clone + Eq + Ord + Hash>(
+    graph: &Graph<T>,
+    start: usize,
+) -> Vec<Option<usize>> {
+    let n = graph.values.len();
+    // postorder numbering of the nodes reachable from start
+    let mut postorder = vec![];
+    let mut order = vec![usize::MAX; n];
+    let mut visited = vec![false; n];
+    let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+    visited[start] = true;
+    while let Some(&mut (v, ref mut pos)) = stack.last_mut() {
+        let deps = graph.deps.get(&v).map_or(&[][..], |vs| vs.as_slice());
+        if let Some(&w) = deps.get(*pos) {
+            *pos += 1;
+            if !visited[w] {
+                visited[w] = true;
+                stack.push((w, 0));
+            }
+            continue;
+        }
+        stack.pop();
+        order[v] = postorder.len();
+        postorder.push(v);
+    }
+    let mut preds: Vec<Vec<usize>> = vec![vec![]; n];
+    for e in graph.to_index_edges() {
+        if visited[e.0] {
+            preds[e.1].push(e.0);
+        }
+    }
+    let mut idom: Vec<Option<usize>> = vec![None; n];
+    idom[start] = Some(start);
+    let intersect = |idom: &[Option<usize>], mut a: usize, mut b: usize| {
+        while a != b {
+            while order[a] < order[b] {
+                a = idom[a].expect("processed");
+            }
+            while order[b] < order[a] {
+                b = idom[b].expect("processed");
+            }
+        }
+        a
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &v in postorder.iter().rev().skip(1) {
+            let mut new_idom = None;
+            for &p in preds[v].iter() {
+                if idom[p].is_none() {
+                    continue;
+                }
+                new_idom = Some(match new_idom {
+                    None => p,
+                    Some(d) => intersect(&idom, p, d),
+                });
+            }
+            if new_idom.is_some() && idom[v] != new_idom {
+                idom[v] = new_idom;
+                changed = true;
+            }
+        }
+    }
+    idom[start] = None;
+    idom
+}
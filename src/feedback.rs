@@ -0,0 +1,111 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::Hash,
+};
+
+use crate::{
+    graph::{Edge, Graph},
+    scc::find_cycles,
+};
+
+// sinks, sources, then the others bucketed by out-degree minus in-degree (offset by `m`)
+fn class_of(outs: usize, ins: usize, m: usize) -> usize {
+    if outs == 0 {
+        0
+    } else if ins == 0 {
+        1
+    } else {
+        2 + m + outs - ins
+    }
+}
+
+// Eades, Lin and Smyth, "A fast and effective heuristic for the feedback arc set problem"
+fn order_component<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>, members: &[usize]) -> Vec<usize> {
+    let local: HashMap<usize, usize> = members.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+    let k = members.len();
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; k];
+    let mut preds: Vec<Vec<usize>> = vec![vec![]; k];
+    for (i, v) in members.iter().enumerate() {
+        for t in graph.deps.get(v).into_iter().flatten() {
+            let Some(&j) = local.get(t) else {
+                continue;
+            };
+            if i != j {
+                succs[i].push(j);
+                preds[j].push(i);
+            }
+        }
+    }
+    let mut outs: Vec<usize> = succs.iter().map(|ts| ts.len()).collect();
+    let mut ins: Vec<usize> = preds.iter().map(|fs| fs.len()).collect();
+    let m: usize = outs.iter().sum();
+    // ordered sets keep ties broken by the smallest node
+    let mut classes: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); 2 * m + 3];
+    let mut class: Vec<usize> = (0..k).map(|i| class_of(outs[i], ins[i], m)).collect();
+    for (i, c) in class.iter().enumerate() {
+        classes[*c].insert(i);
+    }
+    let mut max = classes.len() - 1;
+    let mut removed = vec![false; k];
+    let mut head = vec![];
+    let mut tail = vec![];
+    for _ in 0..k {
+        let next = if let Some(&i) = classes[0].first() {
+            tail.push(i);
+            i
+        } else if let Some(&i) = classes[1].first() {
+            head.push(i);
+            i
+        } else {
+            while classes[max].is_empty() {
+                max -= 1;
+            }
+            let i = *classes[max].first().expect("not empty");
+            head.push(i);
+            i
+        };
+        classes[class[next]].remove(&next);
+        removed[next] = true;
+        let mut touched = vec![];
+        for &j in succs[next].iter().filter(|j| !removed[**j]) {
+            ins[j] -= 1;
+            touched.push(j);
+        }
+        for &j in preds[next].iter().filter(|j| !removed[**j]) {
+            outs[j] -= 1;
+            touched.push(j);
+        }
+        for j in touched {
+            let c = class_of(outs[j], ins[j], m);
+            if c != class[j] {
+                classes[class[j]].remove(&j);
+                classes[c].insert(j);
+                class[j] = c;
+                max = max.max(c);
+            }
+        }
+    }
+    tail.reverse();
+    head.extend(tail);
+    head.into_iter().map(|i| members[i]).collect()
+}
+
+pub(crate) fn feedback_arc_set<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<Edge<usize>> {
+    let mut position = vec![0; graph.values.len()];
+    let mut component = vec![usize::MAX; graph.values.len()];
+    for (c, members) in find_cycles(graph).iter().enumerate() {
+        for (p, n) in order_component(graph, members).into_iter().enumerate() {
+            position[n] = p;
+            component[n] = c;
+        }
+    }
+    graph
+        .to_index_edges()
+        .into_iter()
+        .filter(|e| {
+            component[e.0] != usize::MAX
+                && component[e.0] == component[e.1]
+                && position[e.0] >= position[e.1]
+        })
+        .collect()
+}
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use crate::graph::Attrs;
+
+#[derive(Debug, Clone)]
+pub(crate) struct AttrFilter {
+    key: String,
+    value: Option<String>,
+}
+
+impl AttrFilter {
+    pub(crate) fn matches(&self, attrs: Option<&Attrs>) -> bool {
+        let Some(v) = attrs.and_then(|attrs| attrs.get(&self.key)) else {
+            return false;
+        };
+        self.value.as_ref().is_none_or(|value| v == value)
+    }
+}
+
+impl FromStr for AttrFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = match s.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_owned())),
+            None => (s, None),
+        };
+        if key.is_empty() {
+            return Err(format!("empty key: {:?}", s));
+        }
+        Ok(AttrFilter {
+            key: key.to_owned(),
+            value,
+        })
+    }
+}
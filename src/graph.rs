@@ -3,6 +3,8 @@ use std::{
     hash::Hash,
 };
 
+use crate::consts::{DEFAULT_EDGE_KIND, KIND_ATTR};
+
 #[derive(Debug, Clone)]
 pub(crate) struct Edge<T: Clone>(pub(crate) T, pub(crate) T);
 
@@ -12,11 +14,22 @@ impl<T: Clone> Edge<T> {
     }
 }
 
+pub(crate) type Attrs = BTreeMap<String, String>;
+
+pub(crate) fn edge_kind(attrs: &Attrs) -> &str {
+    attrs
+        .get(KIND_ATTR)
+        .map_or(DEFAULT_EDGE_KIND, |k| k.as_str())
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Graph<T: Clone + Eq + Ord + Hash> {
     pub(crate) values: Vec<T>,
     pub(crate) value_to_index: HashMap<T, usize>,
     pub(crate) deps: HashMap<usize, Vec<usize>>,
+    pub(crate) node_attrs: HashMap<usize, Attrs>,
+    // attributes of each edge instance, parallel to `deps`
+    pub(crate) edge_attrs: HashMap<usize, Vec<Attrs>>,
 }
 
 impl<T: Clone + Eq + Ord + Hash> Default for Graph<T> {
@@ -25,6 +38,8 @@ impl<T: Clone + Eq + Ord + Hash> Default for Graph<T> {
             values: Vec::new(),
             value_to_index: HashMap::new(),
             deps: HashMap::new(),
+            node_attrs: HashMap::new(),
+            edge_attrs: HashMap::new(),
         }
     }
 }
@@ -32,58 +47,184 @@ impl<T: Clone + Eq + Ord + Hash> Default for Graph<T> {
 impl<T: Clone + Eq + Ord + Hash> FromIterator<Edge<T>> for Graph<T> {
     fn from_iter<I: IntoIterator<Item = Edge<T>>>(iter: I) -> Self {
         let mut graph = Graph::<T>::default();
-        let mut seen: HashMap<T, usize> = HashMap::new();
-        let mut values: Vec<T> = vec![];
-        let mut f = |k: &T| match seen.entry(k.clone()) {
-            Entry::Occupied(o) => *o.get(),
-            Entry::Vacant(v) => {
-                let i = values.len();
-                v.insert(i);
-                values.push(k.clone());
-                i
-            }
-        };
         for t in iter {
-            let from = f(&t.0);
-            let to = f(&t.1);
-            match graph.deps.entry(from) {
-                Entry::Occupied(mut o) => o.get_mut().push(to),
-                Entry::Vacant(v) => {
-                    v.insert(vec![to]);
-                }
-            };
+            graph.add_edge(&t);
         }
-        graph.values = values;
-        graph.value_to_index = seen;
         graph
     }
 }
 
 impl<T: Clone + Eq + Ord + Hash> From<HashMap<T, Vec<T>>> for Graph<T> {
     fn from(m: HashMap<T, Vec<T>>) -> Self {
-        let mut edges = Vec::<Edge<T>>::new();
+        let mut graph = Graph::<T>::default();
         for (k, vs) in m.iter() {
+            graph.add_node(k);
             for v in vs.iter() {
-                edges.push(Edge(k.clone(), v.clone()));
+                graph.add_edge(&Edge(k.clone(), v.clone()));
             }
         }
-        Self::from_iter(edges)
+        graph
     }
 }
 
 impl<T: Clone + Eq + Ord + Hash> From<BTreeMap<T, Vec<T>>> for Graph<T> {
     fn from(m: BTreeMap<T, Vec<T>>) -> Self {
-        let mut edges = Vec::<Edge<T>>::new();
+        let mut graph = Graph::<T>::default();
         for (k, vs) in m.iter() {
+            graph.add_node(k);
             for v in vs.iter() {
-                edges.push(Edge(k.clone(), v.clone()));
+                graph.add_edge(&Edge(k.clone(), v.clone()));
             }
         }
-        Self::from_iter(edges)
+        graph
     }
 }
 
 impl<T: Clone + Eq + Ord + Hash> Graph<T> {
+    pub(crate) fn add_node(&mut self, k: &T) -> usize {
+        match self.value_to_index.entry(k.clone()) {
+            Entry::Occupied(o) => *o.get(),
+            Entry::Vacant(v) => {
+                let i = self.values.len();
+                v.insert(i);
+                self.values.push(k.clone());
+                i
+            }
+        }
+    }
+
+    pub(crate) fn add_edge(&mut self, e: &Edge<T>) -> Edge<usize> {
+        self.add_edge_with_attrs(e, Attrs::new())
+    }
+
+    pub(crate) fn add_edge_with_attrs(&mut self, e: &Edge<T>, attrs: Attrs) -> Edge<usize> {
+        let from = self.add_node(&e.0);
+        let to = self.add_node(&e.1);
+        self.add_index_edge(from, to, attrs);
+        Edge(from, to)
+    }
+
+    fn add_index_edge(&mut self, from: usize, to: usize, attrs: Attrs) {
+        self.deps.entry(from).or_default().push(to);
+        self.edge_attrs.entry(from).or_default().push(attrs);
+    }
+
+    pub(crate) fn contains_edge(&self, from: usize, to: usize) -> bool {
+        self.deps.get(&from).is_some_and(|tos| tos.contains(&to))
+    }
+
+    // edges of the same kind between the same nodes are duplicates; their attributes are merged
+    pub(crate) fn dedup(&mut self) -> Vec<(Edge<usize>, Attrs)> {
+        let mut duplicates = vec![];
+        for from in 0..self.values.len() {
+            let Some(tos) = self.deps.remove(&from) else {
+                continue;
+            };
+            let attrs = self.edge_attrs.remove(&from).unwrap_or_default();
+            let mut seen: HashMap<(usize, String), usize> = HashMap::new();
+            let mut kept: Vec<(usize, Attrs)> = vec![];
+            for (to, attrs) in tos.into_iter().zip(attrs) {
+                match seen.entry((to, edge_kind(&attrs).to_owned())) {
+                    Entry::Occupied(o) => {
+                        kept[*o.get()].1.extend(attrs.clone());
+                        duplicates.push((Edge(from, to), attrs));
+                    }
+                    Entry::Vacant(v) => {
+                        v.insert(kept.len());
+                        kept.push((to, attrs));
+                    }
+                }
+            }
+            for (to, attrs) in kept {
+                self.add_index_edge(from, to, attrs);
+            }
+        }
+        duplicates
+    }
+
+    pub(crate) fn find_self_loops(&self) -> Vec<usize> {
+        (0..self.values.len())
+            .filter(|i| self.contains_edge(*i, *i))
+            .collect()
+    }
+
+    pub(crate) fn merge(&mut self, other: &Graph<T>) {
+        for (i, v) in other.values.iter().enumerate() {
+            let j = self.add_node(v);
+            self.add_node_attrs(j, other.node_attrs(i).cloned().unwrap_or_default());
+        }
+        for (e, attrs) in other.to_edges_with_attrs() {
+            self.add_edge_with_attrs(&e, attrs.clone());
+        }
+    }
+
+    pub(crate) fn map_values<F: FnMut(&T) -> T>(&self, f: F) -> Graph<T> {
+        let values: Vec<T> = self.values.iter().map(f).collect();
+        let value_to_index = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.clone(), i))
+            .collect();
+        Graph {
+            values,
+            value_to_index,
+            deps: self.deps.clone(),
+            node_attrs: self.node_attrs.clone(),
+            edge_attrs: self.edge_attrs.clone(),
+        }
+    }
+
+    pub(crate) fn add_node_attrs(&mut self, i: usize, attrs: Attrs) {
+        if !attrs.is_empty() {
+            self.node_attrs.entry(i).or_default().extend(attrs);
+        }
+    }
+
+    pub(crate) fn node_attrs(&self, i: usize) -> Option<&Attrs> {
+        self.node_attrs.get(&i)
+    }
+
+    pub(crate) fn has_attrs(&self) -> bool {
+        !self.node_attrs.is_empty() || self.edge_attrs.values().flatten().any(|a| !a.is_empty())
+    }
+
+    pub(crate) fn filter_nodes<F: FnMut(usize) -> bool>(&self, mut f: F) -> Graph<T> {
+        let mut graph = Self::default();
+        for (i, v) in self.values.iter().enumerate() {
+            if f(i) {
+                let j = graph.add_node(v);
+                graph.add_node_attrs(j, self.node_attrs(i).cloned().unwrap_or_default());
+            }
+        }
+        for (e, attrs) in self.to_edges_with_attrs() {
+            if !graph.value_to_index.contains_key(&e.0) || !graph.value_to_index.contains_key(&e.1)
+            {
+                continue;
+            }
+            graph.add_edge_with_attrs(&e, attrs.clone());
+        }
+        graph
+    }
+
+    pub(crate) fn filter_edges<F: FnMut(usize, usize, &Attrs) -> bool>(
+        &self,
+        mut f: F,
+    ) -> Graph<T> {
+        let mut graph = Graph {
+            values: self.values.clone(),
+            value_to_index: self.value_to_index.clone(),
+            deps: HashMap::new(),
+            node_attrs: self.node_attrs.clone(),
+            edge_attrs: HashMap::new(),
+        };
+        for (e, attrs) in self.to_index_edges_with_attrs() {
+            if f(e.0, e.1, attrs) {
+                graph.add_index_edge(e.0, e.1, attrs.clone());
+            }
+        }
+        graph
+    }
+
     pub(crate) fn find_roots(&self) -> Vec<usize> {
         let mut seen: HashSet<usize> = HashSet::new();
         for (_k, vs) in self.deps.iter() {
@@ -97,6 +238,19 @@ impl<T: Clone + Eq + Ord + Hash> Graph<T> {
         roots
     }
 
+    pub(crate) fn reachable(&self, start: usize) -> HashSet<usize> {
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(n) = stack.pop() {
+            for &t in self.deps.get(&n).into_iter().flatten() {
+                if seen.insert(t) {
+                    stack.push(t);
+                }
+            }
+        }
+        seen
+    }
+
     pub(crate) fn to_index_edges(&self) -> Vec<Edge<usize>> {
         let mut edges = vec![];
         for (from, _) in self.values.iter().enumerate() {
@@ -108,6 +262,30 @@ impl<T: Clone + Eq + Ord + Hash> Graph<T> {
         edges
     }
 
+    pub(crate) fn to_index_edges_with_attrs(&self) -> Vec<(Edge<usize>, &Attrs)> {
+        let mut edges = vec![];
+        for (from, _) in self.values.iter().enumerate() {
+            let Some(tos) = self.deps.get(&from) else { continue; };
+            let attrs = &self.edge_attrs[&from];
+            for (to, attrs) in tos.iter().zip(attrs) {
+                edges.push((Edge(from, *to), attrs))
+            }
+        }
+        edges
+    }
+
+    pub(crate) fn to_edges_with_attrs(&self) -> Vec<(Edge<T>, &Attrs)> {
+        self.to_index_edges_with_attrs()
+            .into_iter()
+            .map(|(e, attrs)| {
+                (
+                    Edge(self.values[e.0].clone(), self.values[e.1].clone()),
+                    attrs,
+                )
+            })
+            .collect()
+    }
+
     pub(crate) fn to_edges(&self) -> Vec<Edge<T>> {
         let mut edges = vec![];
         for (from, k) in self.values.iter().enumerate() {
@@ -120,7 +298,8 @@ impl<T: Clone + Eq + Ord + Hash> Graph<T> {
     }
 
     pub(crate) fn to_btree_map(&self) -> BTreeMap<T, Vec<T>> {
-        self.deps
+        let mut m: BTreeMap<T, Vec<T>> = self
+            .deps
             .iter()
             .map(|(k, vs)| {
                 (
@@ -128,7 +307,13 @@ impl<T: Clone + Eq + Ord + Hash> Graph<T> {
                     vs.iter().map(|v| self.values[*v].clone()).collect(),
                 )
             })
-            .collect()
+            .collect();
+        for i in self.find_roots() {
+            if !self.deps.contains_key(&i) {
+                m.insert(self.values[i].clone(), vec![]);
+            }
+        }
+        m
     }
 
     pub(crate) fn remap(&self, values: Vec<T>) -> Graph<T> {
@@ -149,14 +334,44 @@ impl<T: Clone + Eq + Ord + Hash> Graph<T> {
                 )
             })
             .collect();
+        let node_attrs = self
+            .node_attrs
+            .iter()
+            .map(|(i, attrs)| {
+                (
+                    *value_to_index.get(&self.values[*i]).unwrap(),
+                    attrs.clone(),
+                )
+            })
+            .collect();
+        let edge_attrs = self
+            .edge_attrs
+            .iter()
+            .map(|(k, attrs)| {
+                (
+                    *value_to_index.get(&self.values[*k]).unwrap(),
+                    attrs.clone(),
+                )
+            })
+            .collect();
         Graph {
             values,
             value_to_index,
             deps,
+            node_attrs,
+            edge_attrs,
         }
     }
 
     pub(crate) fn invert(&self) -> Self {
-        Self::from_iter(self.to_edges().iter().map(|e| e.invert()))
+        let mut graph = Self::default();
+        for (e, attrs) in self.to_edges_with_attrs() {
+            graph.add_edge_with_attrs(&e.invert(), attrs.clone());
+        }
+        for (i, v) in self.values.iter().enumerate() {
+            let j = graph.add_node(v);
+            graph.add_node_attrs(j, self.node_attrs(i).cloned().unwrap_or_default());
+        }
+        graph
     }
 }
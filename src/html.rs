@@ -0,0 +1,36 @@
+use std::io::Write;
+
+use anyhow::{Context as _, Result};
+
+use crate::graph::Graph;
+
+const TEMPLATE: &str = include_str!("report.html");
+const PLACEHOLDER: &str = "/*GRAPH*/";
+
+pub(crate) fn write_html<W: Write>(mut w: W, graph: &Graph<String>) -> Result<()> {
+    let nodes = graph
+        .values
+        .iter()
+        .enumerate()
+        .map(|(i, n)| serde_json::json!({ "name": n, "attrs": graph.node_attrs(i).cloned().unwrap_or_default() }))
+        .collect::<Vec<_>>();
+    let edges = graph
+        .to_index_edges()
+        .iter()
+        .map(|e| serde_json::json!([e.0, e.1]))
+        .collect::<Vec<_>>();
+    let data = serde_json::to_string(&serde_json::json!({ "nodes": nodes, "edges": edges }))
+        .context("can't dump html")?;
+    // these only occur inside JSON strings, so escape them to keep names from ending the script element
+    let data = data
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026");
+    let (head, tail) = TEMPLATE
+        .split_once(PLACEHOLDER)
+        .expect("placeholder in template");
+    w.write_all(head.as_bytes())?;
+    w.write_all(data.as_bytes())?;
+    w.write_all(tail.as_bytes())?;
+    Ok(())
+}
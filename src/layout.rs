@@ -0,0 +1,234 @@
+use std::{collections::HashSet, hash::Hash};
+
+use crate::{feedback::feedback_arc_set, graph::Graph};
+
+const RANK_GAP: f64 = 50.0;
+const NODE_GAP: f64 = 20.0;
+const SWEEPS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RankDir {
+    TopBottom,
+    BottomTop,
+    LeftRight,
+    RightLeft,
+}
+
+impl RankDir {
+    pub(crate) fn parse(s: &str) -> Option<RankDir> {
+        match s.to_ascii_uppercase().as_str() {
+            "TB" => Some(RankDir::TopBottom),
+            "BT" => Some(RankDir::BottomTop),
+            "LR" => Some(RankDir::LeftRight),
+            "RL" => Some(RankDir::RightLeft),
+            _ => None,
+        }
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, RankDir::LeftRight | RankDir::RightLeft)
+    }
+}
+
+pub(crate) type Point = (f64, f64);
+
+#[derive(Debug, Clone)]
+pub(crate) struct Layout {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    // center of each node
+    pub(crate) nodes: Vec<Point>,
+    // (from, to, points from the border of `from` to the border of `to`)
+    pub(crate) edges: Vec<(usize, usize, Vec<Point>)>,
+}
+
+fn longest_path_ranks(n: usize, succs: &[Vec<usize>]) -> Vec<usize> {
+    let mut indegree = vec![0; n];
+    for ts in succs.iter() {
+        for &t in ts.iter() {
+            indegree[t] += 1;
+        }
+    }
+    let mut rank = vec![0; n];
+    let mut stack: Vec<usize> = (0..n).filter(|v| indegree[*v] == 0).rev().collect();
+    while let Some(v) = stack.pop() {
+        for &t in succs[v].iter() {
+            rank[t] = rank[t].max(rank[v] + 1);
+            indegree[t] -= 1;
+            if indegree[t] == 0 {
+                stack.push(t);
+            }
+        }
+    }
+    rank
+}
+
+fn order_by_barycenter(layer: &mut [usize], neighbors: &[Vec<usize>], position: &[f64]) {
+    let key = |v: usize| {
+        let ps = &neighbors[v];
+        if ps.is_empty() {
+            position[v]
+        } else {
+            ps.iter().map(|p| position[*p]).sum::<f64>() / ps.len() as f64
+        }
+    };
+    let mut keyed: Vec<(f64, usize)> = layer.iter().map(|v| (key(*v), *v)).collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for (slot, (_, v)) in layer.iter_mut().zip(keyed) {
+        *slot = v;
+    }
+}
+
+// `sizes` are (width, height) of each node
+pub(crate) fn layout<T: Clone + Eq + Ord + Hash>(
+    graph: &Graph<T>,
+    sizes: &[(f64, f64)],
+    rankdir: RankDir,
+) -> Layout {
+    let n = graph.values.len();
+    let reversed: HashSet<(usize, usize)> = feedback_arc_set(graph)
+        .into_iter()
+        .map(|e| (e.0, e.1))
+        .collect();
+    let edges: Vec<(usize, usize)> = graph
+        .to_index_edges()
+        .into_iter()
+        .filter(|e| e.0 != e.1)
+        .map(|e| (e.0, e.1))
+        .collect();
+    let acyclic = |e: &(usize, usize)| if reversed.contains(e) { (e.1, e.0) } else { *e };
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; n];
+    for e in edges.iter().map(acyclic) {
+        succs[e.0].push(e.1);
+    }
+    let mut rank = longest_path_ranks(n, &succs);
+
+    // split long edges with dummy nodes so every edge spans one rank
+    let mut chains: Vec<Vec<usize>> = vec![];
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut preds: Vec<Vec<usize>> = vec![vec![]; n];
+    for e in edges.iter().map(acyclic) {
+        let mut chain = vec![e.0];
+        for r in rank[e.0] + 1..rank[e.1] {
+            rank.push(r);
+            succs.push(vec![]);
+            preds.push(vec![]);
+            chain.push(rank.len() - 1);
+        }
+        chain.push(e.1);
+        for w in chain.windows(2) {
+            succs[w[0]].push(w[1]);
+            preds[w[1]].push(w[0]);
+        }
+        chains.push(chain);
+    }
+    let total = rank.len();
+    let (along, across): (Vec<f64>, Vec<f64>) = (0..total)
+        .map(|v| match sizes.get(v).filter(|_| v < n) {
+            Some(&(w, h)) if rankdir.is_horizontal() => (w, h),
+            Some(&(w, h)) => (h, w),
+            None => (0.0, 0.0),
+        })
+        .unzip();
+
+    let ranks = rank.iter().copied().max().map_or(0, |r| r + 1);
+    let mut layers: Vec<Vec<usize>> = vec![vec![]; ranks];
+    for (v, r) in rank.iter().enumerate() {
+        layers[*r].push(v);
+    }
+    let mut position = vec![0.0; total];
+    let place = |layers: &[Vec<usize>], position: &mut [f64]| {
+        for layer in layers.iter() {
+            for (i, v) in layer.iter().enumerate() {
+                position[*v] = i as f64;
+            }
+        }
+    };
+    place(&layers, &mut position);
+    for sweep in 0..SWEEPS {
+        if sweep % 2 == 0 {
+            for r in 1..ranks {
+                order_by_barycenter(&mut layers[r], &preds, &position);
+                place(&layers, &mut position);
+            }
+        } else {
+            for r in (0..ranks.saturating_sub(1)).rev() {
+                order_by_barycenter(&mut layers[r], &succs, &position);
+                place(&layers, &mut position);
+            }
+        }
+    }
+
+    // pack each layer and center it on the widest one
+    let extents: Vec<f64> = layers
+        .iter()
+        .map(|l| {
+            l.iter().map(|v| across[*v]).sum::<f64>() + NODE_GAP * l.len().saturating_sub(1) as f64
+        })
+        .collect();
+    let breadth = extents.iter().copied().fold(0.0, f64::max);
+    let mut cross = vec![0.0; total];
+    for (layer, extent) in layers.iter().zip(extents.iter()) {
+        let mut offset = (breadth - extent) / 2.0 + NODE_GAP;
+        for &v in layer.iter() {
+            cross[v] = offset + across[v] / 2.0;
+            offset += across[v] + NODE_GAP;
+        }
+    }
+    let depths: Vec<f64> = layers
+        .iter()
+        .map(|l| l.iter().map(|v| along[*v]).fold(0.0, f64::max))
+        .collect();
+    let mut rank_center = vec![0.0; ranks];
+    let mut offset = RANK_GAP / 2.0;
+    for (r, depth) in depths.iter().enumerate() {
+        rank_center[r] = offset + depth / 2.0;
+        offset += depth + RANK_GAP;
+    }
+    let length = offset - RANK_GAP / 2.0;
+    let breadth = breadth + NODE_GAP * 2.0;
+
+    let point = |rank_pos: f64, cross_pos: f64| match rankdir {
+        RankDir::TopBottom => (cross_pos, rank_pos),
+        RankDir::BottomTop => (cross_pos, length - rank_pos),
+        RankDir::LeftRight => (rank_pos, cross_pos),
+        RankDir::RightLeft => (length - rank_pos, cross_pos),
+    };
+    let nodes = (0..n)
+        .map(|v| point(rank_center[rank[v]], cross[v]))
+        .collect();
+    let edges = edges
+        .iter()
+        .zip(chains)
+        .map(|(e, chain)| {
+            let first = chain[0];
+            let last = chain[chain.len() - 1];
+            let mut points = vec![point(
+                rank_center[rank[first]] + along[first] / 2.0,
+                cross[first],
+            )];
+            for &v in chain[1..chain.len() - 1].iter() {
+                points.push(point(rank_center[rank[v]], cross[v]));
+            }
+            points.push(point(
+                rank_center[rank[last]] - along[last] / 2.0,
+                cross[last],
+            ));
+            if reversed.contains(e) {
+                points.reverse();
+            }
+            (e.0, e.1, points)
+        })
+        .collect();
+    let (width, height) = if rankdir.is_horizontal() {
+        (length, breadth)
+    } else {
+        (breadth, length)
+    };
+    Layout {
+        width,
+        height,
+        nodes,
+        edges,
+    }
+}
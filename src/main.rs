@@ -1,27 +1,52 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fs::File,
-    io::{stdin, stdout, BufRead, BufReader, BufWriter, Write},
+    io::{stdin, stdout, BufRead, BufReader, BufWriter, IsTerminal, Write},
     path::{Path, PathBuf},
     process::exit,
 };
 
-use anyhow::{Context as _, Result};
-use clap::{self, Parser};
-use graph::{Edge, Graph};
+use anyhow::{bail, Context as _, Result};
+use clap::{self, Parser, ValueEnum};
+use diff::GraphDiff;
+use filter::AttrFilter;
+use graph::{edge_kind, Attrs, Edge, Graph};
 use itertools::Itertools;
 use log::{debug, warn};
+use ninja::InputKind as NinjaInputKind;
+use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
 
-use crate::consts::DEFAULT_MAX_DEPTH;
+use crate::consts::{
+    COMPONENT_ATTR, DEFAULT_DOT_KIND_STYLES, DEFAULT_MAX_DEPTH, DOT_ATTRS, DOT_ATTR_PREFIX,
+    KIND_ATTR, SVG_CHAR_WIDTH, SVG_NODE_HEIGHT, WEIGHT_ATTR,
+};
 
 mod bfs;
+mod check;
+mod cluster;
 mod consts;
+mod critical_path;
+mod csv;
 mod dfs;
+mod diff;
+mod dijkstra;
+mod dominator;
+mod feedback;
+mod filter;
 mod graph;
+mod html;
+mod layout;
 #[macro_use]
 mod macros;
+mod ninja;
+mod rank;
+mod scc;
+mod stats;
+mod term;
+mod tree;
 mod tsort;
+mod wcc;
 
 #[derive(Debug, Clone, Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -36,19 +61,53 @@ enum Subcommand {
     Dfs(DfsArgs),
     Bfs(BfsArgs),
     Tsort(TsortArgs),
+    CriticalPath(CriticalPathArgs),
+    ShortestPath(ShortestPathArgs),
+    Diff(DiffArgs),
+    Check(CheckArgs),
+    Layers(LayersArgs),
+    Stats(StatsArgs),
+    Rank(RankArgs),
+    Dominators(DominatorsArgs),
+    Components(ComponentsArgs),
+    BreakCycles(BreakCyclesArgs),
+    Draw(DrawArgs),
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
 enum InputFormat {
     Text,
     Json,
+    Ninja,
+    GoModGraph,
+    Tsort,
+    Adjacency,
+    Csv,
+    Tsv,
 }
 
 impl InputFormat {
     fn assume_from_path(p: &Path) -> InputFormat {
-        let Some(ext) = p.extension().map(|v| v.to_ascii_lowercase().to_string_lossy().to_string()) else { return InputFormat::Text };
+        if p.file_name() == Some("build.ninja".as_ref()) {
+            return InputFormat::Ninja;
+        }
+        let Some(ext) = p
+            .extension()
+            .map(|v| v.to_ascii_lowercase().to_string_lossy().to_string())
+        else {
+            return InputFormat::Text;
+        };
         match ext.as_str() {
             "json" => InputFormat::Json,
+            "ninja" => InputFormat::Ninja,
+            "csv" => InputFormat::Csv,
+            "tsv" => InputFormat::Tsv,
             _ => InputFormat::Text,
         }
     }
@@ -59,25 +118,116 @@ enum OutputFormat {
     Text,
     Json,
     Dot,
+    Adjacency,
+    Csv,
+    Tsv,
+    Html,
+    Svg,
 }
 
 impl OutputFormat {
     fn assume_from_path(p: &Path) -> OutputFormat {
-        let Some(ext) = p.extension().map(|v| v.to_ascii_lowercase().to_string_lossy().to_string()) else { return OutputFormat::Text };
+        let Some(ext) = p
+            .extension()
+            .map(|v| v.to_ascii_lowercase().to_string_lossy().to_string())
+        else {
+            return OutputFormat::Text;
+        };
         match ext.as_str() {
             "json" => OutputFormat::Json,
             "dot" => OutputFormat::Dot,
+            "csv" => OutputFormat::Csv,
+            "tsv" => OutputFormat::Tsv,
+            "html" | "htm" => OutputFormat::Html,
+            "svg" => OutputFormat::Svg,
             _ => OutputFormat::Text,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Severity {
+    Ignore,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct LoadArgs {
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [NinjaInputKind::Explicit, NinjaInputKind::Implicit, NinjaInputKind::OrderOnly]
+    )]
+    ninja_inputs: Vec<NinjaInputKind>,
+    #[clap(long)]
+    go_strip_versions: bool,
+    #[clap(long, default_value = "from")]
+    csv_from: String,
+    #[clap(long, default_value = "to")]
+    csv_to: String,
+    #[clap(long)]
+    node_attr: Vec<AttrFilter>,
+    #[clap(long)]
+    edge_attr: Vec<AttrFilter>,
+    #[clap(long)]
+    multigraph: bool,
+    #[clap(long, value_enum, default_value = "warn")]
+    on_duplicate: Severity,
+    #[clap(long, value_enum, default_value = "warn")]
+    on_self_loop: Severity,
+    #[clap(long)]
+    namespace: bool,
+    #[clap(long, default_value = "/")]
+    namespace_separator: String,
+    #[clap(long, value_delimiter = ',')]
+    edge_kind: Vec<String>,
+    #[clap(long, value_delimiter = ',')]
+    exclude_edge_kind: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonEdge {
+    from: String,
+    to: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attrs: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonGraph {
+    #[serde(default)]
+    nodes: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+    #[serde(default)]
+    edges: Vec<JsonEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonInput {
+    Deps(BTreeMap<String, Vec<String>>),
+    Graph(JsonGraph),
+}
+
 #[derive(Debug, Clone, clap::Args)]
 struct ShowArgs {
     #[clap(short, long, value_enum)]
     from: Option<InputFormat>,
     #[clap(short = 'I', long)]
     inverted: bool,
+    #[clap(long)]
+    condense: bool,
+    #[clap(flatten)]
+    dump: DumpArgs,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, clap::Args)]
+struct DumpArgs {
     #[clap(short, long, value_enum)]
     to: Option<OutputFormat>,
     #[clap(short, long)]
@@ -86,8 +236,31 @@ struct ShowArgs {
     dot_rankdir: Option<String>,
     #[clap(long)]
     dot_metadata: Option<String>,
-    #[clap(name = "FILE", default_value = "-")]
-    file: PathBuf,
+    #[clap(long)]
+    dot_kind_style: Vec<String>,
+    #[clap(long, value_parser = clap::builder::NonEmptyStringValueParser::new())]
+    dot_cluster_separator: Option<String>,
+    #[clap(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    dot_cluster_depth: Option<usize>,
+    #[clap(
+        long,
+        requires = "dot_cluster_separator",
+        requires = "dot_cluster_depth"
+    )]
+    dot_collapse: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum TreePrefix {
+    Indent,
+    Depth,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum TreeCharset {
+    Utf8,
+    Ascii,
 }
 
 #[derive(Debug, Clone, clap::Args)]
@@ -100,10 +273,16 @@ struct DfsArgs {
     start: Option<String>,
     #[clap(short = 'T', long)]
     tree: bool,
+    #[clap(long, value_enum, conflicts_with = "path")]
+    prefix: Option<TreePrefix>,
+    #[clap(long, value_enum, default_value = "utf8", requires = "prefix")]
+    charset: TreeCharset,
     #[clap(long)]
     max_depth: Option<usize>,
+    #[clap(flatten)]
+    load: LoadArgs,
     #[clap(name = "FILE", default_value = "-")]
-    file: PathBuf,
+    files: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, clap::Args)]
@@ -116,43 +295,510 @@ struct BfsArgs {
     start: Option<String>,
     #[clap(long)]
     max_depth: Option<usize>,
+    #[clap(flatten)]
+    load: LoadArgs,
     #[clap(name = "FILE", default_value = "-")]
-    file: PathBuf,
+    files: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, clap::Args)]
 struct TsortArgs {
     #[clap(short, long, value_enum)]
     from: Option<InputFormat>,
+    #[clap(long)]
+    condense: bool,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct CriticalPathArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short = 'W', long)]
+    weights: Option<PathBuf>,
+    #[clap(long, default_value = "weight")]
+    weight_attr: String,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct ShortestPathArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: ReportFormat,
+    #[clap(short = 'S', long)]
+    start: String,
+    #[clap(short = 'E', long)]
+    end: String,
+    #[clap(long, default_value = "weight")]
+    weight_attr: String,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum DiffFormat {
+    Text,
+    Json,
+    Dot,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct DiffArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: DiffFormat,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "OLD")]
+    old: PathBuf,
+    #[clap(name = "NEW")]
+    new: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum CheckFormat {
+    Text,
+    Junit,
+    Sarif,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct CheckArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: CheckFormat,
+    #[clap(short, long)]
+    rules: PathBuf,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum LayersFormat {
+    Text,
+    Json,
+    Dot,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct LayersArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: LayersFormat,
+    #[clap(short, long)]
+    rules: PathBuf,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct StatsArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: ReportFormat,
+    #[clap(short = 'n', long, default_value = "10")]
+    top: usize,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum RankFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum RankKey {
+    Pagerank,
+    Betweenness,
+    Dependents,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct RankArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: RankFormat,
+    #[clap(short, long, value_enum, default_value = "pagerank")]
+    sort: RankKey,
+    #[clap(short = 'n', long)]
+    top: Option<usize>,
+    #[clap(long, default_value = "0.85")]
+    damping: f64,
+    #[clap(flatten)]
+    load: LoadArgs,
     #[clap(name = "FILE", default_value = "-")]
-    file: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum DominatorsFormat {
+    Text,
+    Json,
+    Dot,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct DominatorsArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short, long, value_enum, default_value = "text")]
+    to: DominatorsFormat,
+    #[clap(short = 'S', long)]
+    start: String,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct ComponentsArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short = 'A', long, conflicts_with = "split")]
+    attr: bool,
+    #[clap(long)]
+    split: Option<String>,
+    #[clap(flatten)]
+    dump: DumpArgs,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct BreakCyclesArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short = 'G', long)]
+    graph: bool,
+    #[clap(short, long, value_enum, requires = "graph")]
+    to: Option<OutputFormat>,
+    #[clap(short, long, requires = "graph")]
+    output: Option<PathBuf>,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct DrawArgs {
+    #[clap(short, long, value_enum)]
+    from: Option<InputFormat>,
+    #[clap(short = 'S', long)]
+    start: Option<String>,
+    #[clap(long)]
+    ascii: bool,
+    #[clap(long, value_enum, default_value = "auto")]
+    color: ColorMode,
+    #[clap(flatten)]
+    load: LoadArgs,
+    #[clap(name = "FILE", default_value = "-")]
+    files: Vec<PathBuf>,
 }
 
 fn load_text<R: BufRead>(r: R) -> Result<Graph<String>> {
-    itertools::process_results(r.lines(), |iter| {
-        let edges = iter.map(|s| {
-            let parts: Vec<&str> = s.splitn(2, ' ').collect();
-            Edge(parts[0].to_owned(), parts[1].to_owned())
-        });
-        Graph::from_iter(edges)
-    })
-    .context("can't load text")
+    let mut graph = Graph::default();
+    for (i, line) in r.lines().enumerate() {
+        let line = line.context("can't load text")?;
+        if line.is_empty() {
+            continue;
+        }
+        // the target may contain spaces, so the kind and weight columns are separated by tabs
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() > 3 {
+            bail!(
+                "can't load text: line {}: expected at most 3 tab-separated fields",
+                i + 1
+            );
+        }
+        let Some((from, to)) = parts[0].split_once(' ') else {
+            bail!("can't load text: line {}: expected 2 fields", i + 1)
+        };
+        let mut attrs = Attrs::new();
+        if let Some(kind) = parts.get(1).filter(|k| !k.is_empty()) {
+            attrs.insert(KIND_ATTR.to_owned(), (*kind).to_owned());
+        }
+        if let Some(weight) = parts.get(2) {
+            attrs.insert(WEIGHT_ATTR.to_owned(), (*weight).to_owned());
+        }
+        graph.add_edge_with_attrs(&Edge(from.to_owned(), to.to_owned()), attrs);
+    }
+    Ok(graph)
+}
+
+fn json_to_attrs(m: BTreeMap<String, serde_json::Value>) -> Attrs {
+    m.into_iter()
+        .map(|(k, v)| match v {
+            serde_json::Value::String(s) => (k, s),
+            v => (k, v.to_string()),
+        })
+        .collect()
+}
+
+fn attrs_to_json(attrs: Option<&Attrs>) -> BTreeMap<String, serde_json::Value> {
+    attrs
+        .into_iter()
+        .flatten()
+        .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+        .collect()
 }
 
 fn load_json<R: BufRead>(r: R) -> Result<Graph<String>> {
-    let deps: BTreeMap<String, Vec<String>> =
-        serde_json::from_reader(r).context("can't load json")?;
-    Ok(Graph::from(deps))
+    let input: JsonInput = serde_json::from_reader(r).context("can't load json")?;
+    match input {
+        JsonInput::Deps(deps) => Ok(Graph::from(deps)),
+        JsonInput::Graph(json_graph) => {
+            let mut graph = Graph::default();
+            for (n, attrs) in json_graph.nodes {
+                let i = graph.add_node(&n);
+                graph.add_node_attrs(i, json_to_attrs(attrs));
+            }
+            for e in json_graph.edges {
+                graph.add_edge_with_attrs(&Edge(e.from, e.to), json_to_attrs(e.attrs));
+            }
+            Ok(graph)
+        }
+    }
+}
+
+fn load_ninja<R: BufRead>(r: R, dir: &Path, subargs: &LoadArgs) -> Result<Graph<String>> {
+    let edges = ninja::parse(r, dir).context("can't load ninja")?;
+    let mut graph = Graph::default();
+    for e in edges {
+        if !subargs.ninja_inputs.contains(&e.kind) {
+            continue;
+        }
+        let kind = e.kind.to_possible_value().expect("no skipped kinds");
+        let attrs = Attrs::from([(KIND_ATTR.to_owned(), kind.get_name().to_owned())]);
+        let ie = graph.add_edge_with_attrs(&Edge(e.target, e.input), attrs);
+        graph.add_node_attrs(ie.0, Attrs::from([("rule".to_owned(), e.rule)]));
+    }
+    Ok(graph)
+}
+
+fn load_go_mod_graph<R: BufRead>(r: R, subargs: &LoadArgs) -> Result<Graph<String>> {
+    let module = |s: &str| -> String {
+        if subargs.go_strip_versions {
+            s.split_once('@')
+                .map_or(s, |(path, _version)| path)
+                .to_owned()
+        } else {
+            s.to_owned()
+        }
+    };
+    let mut edges = vec![];
+    for (i, line) in r.lines().enumerate() {
+        let line = line.context("can't load go mod graph")?;
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => continue,
+            [from, to] => edges.push((module(from), module(to))),
+            _ => bail!("can't load go mod graph: line {}: expected 2 fields", i + 1),
+        }
+    }
+    Ok(edges
+        .into_iter()
+        .unique()
+        .map(|(from, to)| Edge(from, to))
+        .collect())
 }
 
-fn load<R: BufRead>(r: R, format: InputFormat) -> Result<Graph<String>> {
+fn load_tsort<R: BufRead>(r: R) -> Result<Graph<String>> {
+    let mut tokens: Vec<String> = vec![];
+    for line in r.lines() {
+        let line = line.context("can't load tsort")?;
+        tokens.extend(line.split_whitespace().map(|s| s.to_owned()));
+    }
+    if tokens.len() % 2 == 1 {
+        bail!("can't load tsort: input contains an odd number of tokens");
+    }
+    let mut graph = Graph::default();
+    for pair in tokens.chunks(2) {
+        if pair[0] == pair[1] {
+            graph.add_node(&pair[0]);
+        } else {
+            graph.add_edge(&Edge(pair[0].clone(), pair[1].clone()));
+        }
+    }
+    Ok(graph)
+}
+
+fn load_adjacency<R: BufRead>(r: R) -> Result<Graph<String>> {
+    let mut graph = Graph::default();
+    for (i, line) in r.lines().enumerate() {
+        let line = line.context("can't load adjacency")?;
+        let line = line
+            .split_once('#')
+            .map_or(line.as_str(), |(s, _comment)| s);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((node, deps)) = line
+            .match_indices(':')
+            .map(|(j, _)| (&line[..j], &line[j + 1..]))
+            .find(|(_, deps)| deps.is_empty() || deps.starts_with(char::is_whitespace))
+        else {
+            bail!("can't load adjacency: line {}: expected ':'", i + 1)
+        };
+        let node = node.trim().to_owned();
+        graph.add_node(&node);
+        for dep in deps.split_whitespace() {
+            graph.add_edge(&Edge(node.clone(), dep.to_owned()));
+        }
+    }
+    Ok(graph)
+}
+
+fn load_csv<R: BufRead>(r: R, delimiter: char, subargs: &LoadArgs) -> Result<Graph<String>> {
+    let records = csv::read_records(r, delimiter).context("can't load csv")?;
+    let Some((header, rows)) = records.split_first() else {
+        return Ok(Graph::default());
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h == name)
+            .with_context(|| format!("can't load csv: no column named {:?}", name))
+    };
+    let from = column(&subargs.csv_from)?;
+    let to = column(&subargs.csv_to)?;
+    let mut graph = Graph::default();
+    for (i, row) in rows.iter().enumerate() {
+        if row.len() != header.len() {
+            bail!(
+                "can't load csv: record {}: expected {} fields",
+                i + 1,
+                header.len()
+            );
+        }
+        let attrs: Attrs = header
+            .iter()
+            .zip(row.iter())
+            .enumerate()
+            .filter(|(j, (_, v))| *j != from && *j != to && !v.is_empty())
+            .map(|(_, (k, v))| (k.clone(), v.clone()))
+            .collect();
+        graph.add_edge_with_attrs(&Edge(row[from].clone(), row[to].clone()), attrs);
+    }
+    Ok(graph)
+}
+
+fn check_loaded(graph: &mut Graph<String>, subargs: &LoadArgs) -> Result<()> {
+    if !subargs.multigraph {
+        let duplicates = graph.dedup();
+        if !duplicates.is_empty() && subargs.on_duplicate != Severity::Ignore {
+            for (e, attrs) in duplicates.iter() {
+                match attrs.get(KIND_ATTR) {
+                    Some(kind) => eprintln!(
+                        "duplicate: {} {} {}",
+                        graph.values[e.0], graph.values[e.1], kind
+                    ),
+                    None => eprintln!("duplicate: {} {}", graph.values[e.0], graph.values[e.1]),
+                }
+            }
+            if subargs.on_duplicate == Severity::Error {
+                bail!("contains duplicate edges");
+            }
+        }
+    }
+    let self_loops = graph.find_self_loops();
+    if !self_loops.is_empty() && subargs.on_self_loop != Severity::Ignore {
+        for v in self_loops.iter() {
+            eprintln!("self-loop: {}", graph.values[*v]);
+        }
+        if subargs.on_self_loop == Severity::Error {
+            bail!("contains self-loops");
+        }
+    }
+    Ok(())
+}
+
+fn filter(mut graph: Graph<String>, subargs: &LoadArgs) -> Graph<String> {
+    if !subargs.node_attr.is_empty() {
+        graph = graph.filter_nodes(|i| {
+            subargs
+                .node_attr
+                .iter()
+                .all(|f| f.matches(graph.node_attrs(i)))
+        });
+    }
+    if !subargs.edge_attr.is_empty() {
+        graph = graph
+            .filter_edges(|_, _, attrs| subargs.edge_attr.iter().all(|f| f.matches(Some(attrs))));
+    }
+    if !subargs.edge_kind.is_empty() || !subargs.exclude_edge_kind.is_empty() {
+        graph = graph.filter_edges(|_, _, attrs| {
+            let kind = edge_kind(attrs);
+            (subargs.edge_kind.is_empty() || subargs.edge_kind.iter().any(|k| k == kind))
+                && !subargs.exclude_edge_kind.iter().any(|k| k == kind)
+        });
+    }
+    graph
+}
+
+fn read<R: BufRead>(
+    r: R,
+    p: &Path,
+    format: InputFormat,
+    subargs: &LoadArgs,
+) -> Result<Graph<String>> {
     match format {
         InputFormat::Text => load_text(r),
         InputFormat::Json => load_json(r),
+        InputFormat::Ninja => {
+            let dir = match p.parent() {
+                Some(dir) if p != Path::new("-") && dir != Path::new("") => dir,
+                _ => Path::new("."),
+            };
+            load_ninja(r, dir, subargs)
+        }
+        InputFormat::GoModGraph => load_go_mod_graph(r, subargs),
+        InputFormat::Tsort => load_tsort(r),
+        InputFormat::Adjacency => load_adjacency(r),
+        InputFormat::Csv => load_csv(r, ',', subargs),
+        InputFormat::Tsv => load_csv(r, '\t', subargs),
     }
 }
 
-fn load_with_path(p: &Path, format: Option<InputFormat>) -> Result<Graph<String>> {
+fn read_with_path(
+    p: &Path,
+    format: Option<InputFormat>,
+    subargs: &LoadArgs,
+) -> Result<Graph<String>> {
     let format = format
         .as_ref()
         .cloned()
@@ -161,20 +807,118 @@ fn load_with_path(p: &Path, format: Option<InputFormat>) -> Result<Graph<String>
     if p == Path::new("-") {
         let stdin_lock = stdin().lock();
         let r = BufReader::new(stdin_lock);
-        load(r, format)
+        read(r, p, format, subargs)
     } else {
         let f = File::open(p)?;
         let r = BufReader::new(f);
-        load(r, format)
+        read(r, p, format, subargs)
+    }
+}
+
+fn load_with_path(
+    p: &Path,
+    format: Option<InputFormat>,
+    subargs: &LoadArgs,
+) -> Result<Graph<String>> {
+    load_with_paths(&[p.to_path_buf()], format, subargs)
+}
+
+fn load_with_paths(
+    ps: &[PathBuf],
+    format: Option<InputFormat>,
+    subargs: &LoadArgs,
+) -> Result<Graph<String>> {
+    if ps.iter().filter(|p| *p == Path::new("-")).count() > 1 {
+        bail!("stdin (-) is given more than once");
     }
+    let mut namespaces = HashMap::new();
+    let mut graph = Graph::default();
+    for p in ps {
+        let g = read_with_path(p, format.clone(), subargs)?;
+        if subargs.namespace {
+            let namespace = match p.file_stem() {
+                Some(stem) if p != Path::new("-") => stem.to_string_lossy().to_string(),
+                _ => "stdin".to_owned(),
+            };
+            if let Some(other) = namespaces.insert(namespace.clone(), p) {
+                bail!(
+                    "{} and {} have the same namespace: {}",
+                    other.display(),
+                    p.display(),
+                    namespace
+                );
+            }
+            let g = g.map_values(|v| format!("{}{}{}", namespace, subargs.namespace_separator, v));
+            graph.merge(&g);
+        } else {
+            graph.merge(&g);
+        }
+    }
+    check_loaded(&mut graph, subargs)?;
+    Ok(filter(graph, subargs))
 }
 
 fn dump_text<W: Write>(mut w: W, graph: Graph<String>) -> Result<()> {
-    for e in graph.to_edges() {
-        w.write_all(e.0.as_bytes())?;
-        w.write_all(&[b' '])?;
-        w.write_all(e.1.as_bytes())?;
-        w.write_all(&[b'\n'])?;
+    for (e, attrs) in graph.to_index_edges_with_attrs() {
+        w.write_all(graph.values[e.0].as_bytes())?;
+        w.write_all(b" ")?;
+        w.write_all(graph.values[e.1].as_bytes())?;
+        let (kind, weight) = (attrs.get(KIND_ATTR), attrs.get(WEIGHT_ATTR));
+        if kind.is_some() || weight.is_some() {
+            w.write_all(b"\t")?;
+            w.write_all(kind.map_or("", |k| k.as_str()).as_bytes())?;
+        }
+        if let Some(weight) = weight {
+            w.write_all(b"\t")?;
+            w.write_all(weight.as_bytes())?;
+        }
+        w.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn dump_adjacency<W: Write>(mut w: W, graph: Graph<String>) -> Result<()> {
+    let roots = graph.find_roots();
+    for (i, n) in graph.values.iter().enumerate() {
+        let deps = graph.deps.get(&i);
+        if deps.is_none() && roots.binary_search(&i).is_err() {
+            continue;
+        }
+        w.write_all(n.as_bytes())?;
+        w.write_all(b":")?;
+        for dep in deps.into_iter().flatten() {
+            w.write_all(b" ")?;
+            w.write_all(graph.values[*dep].as_bytes())?;
+        }
+        w.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn dump_csv<W: Write>(
+    mut w: W,
+    graph: Graph<String>,
+    delimiter: char,
+    load: &LoadArgs,
+) -> Result<()> {
+    let keys: Vec<&String> = graph
+        .edge_attrs
+        .values()
+        .flatten()
+        .flat_map(|attrs| attrs.keys())
+        .sorted()
+        .dedup()
+        .collect();
+    let mut header = vec![&load.csv_from, &load.csv_to];
+    header.extend(keys.iter());
+    csv::write_record(&mut w, &header, delimiter)?;
+    for (e, attrs) in graph.to_index_edges_with_attrs() {
+        let mut record = vec![graph.values[e.0].as_str(), graph.values[e.1].as_str()];
+        record.extend(
+            keys.iter()
+                .map(|k| attrs.get(*k).map_or("", |v| v.as_str())),
+        );
+        csv::write_record(&mut w, &record, delimiter)?;
     }
     Ok(())
 }
@@ -183,7 +927,64 @@ fn dot_quote(s: &str) -> String {
     serde_json::to_string(s).expect("can't serialize")
 }
 
-fn dump_dot<W: Write>(mut w: W, graph: Graph<String>, subargs: &ShowArgs) -> Result<()> {
+fn dot_id(s: &str) -> String {
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        s.to_owned()
+    } else {
+        dot_quote(s)
+    }
+}
+
+fn dot_attrs<'a, I: IntoIterator<Item = (&'a String, &'a String)>>(attrs: I) -> String {
+    attrs
+        .into_iter()
+        .map(|(k, v)| {
+            if DOT_ATTRS.contains(&k.as_str()) {
+                format!("{}={}", dot_id(k), dot_quote(v))
+            } else {
+                format!(
+                    "{}={}",
+                    dot_id(&format!("{}{}", DOT_ATTR_PREFIX, k)),
+                    dot_quote(v)
+                )
+            }
+        })
+        .join(", ")
+}
+
+fn dump_dot_node<W: Write>(w: &mut W, graph: &Graph<String>, i: usize, level: usize) -> Result<()> {
+    let label = ("label".to_owned(), graph.values[i].clone());
+    let attrs = graph.node_attrs(i).into_iter().flatten();
+    let attrs = match graph.node_attrs(i) {
+        Some(a) if a.contains_key("label") => dot_attrs(attrs),
+        _ => dot_attrs(std::iter::once((&label.0, &label.1)).chain(attrs)),
+    };
+    w.write_all(format!("{}n{} [{}];\n", "    ".repeat(level), i, attrs).as_bytes())?;
+    Ok(())
+}
+
+fn dump_dot_cluster<W: Write>(
+    w: &mut W,
+    graph: &Graph<String>,
+    cluster: &cluster::Cluster,
+    level: usize,
+    next_id: &mut usize,
+) -> Result<()> {
+    let indent = "    ".repeat(level);
+    for (name, child) in cluster.children.iter() {
+        writeln!(w, "{}subgraph cluster_{} {{", indent, next_id)?;
+        *next_id += 1;
+        writeln!(w, "{}    label={};", indent, dot_quote(name))?;
+        dump_dot_cluster(w, graph, child, level + 1, next_id)?;
+        writeln!(w, "{}}}", indent)?;
+    }
+    for &i in cluster.nodes.iter() {
+        dump_dot_node(w, graph, i, level)?;
+    }
+    Ok(())
+}
+
+fn dump_dot<W: Write>(mut w: W, graph: Graph<String>, subargs: &DumpArgs) -> Result<()> {
     w.write_all(b"digraph {\n")?;
     if let Some(metadata) = subargs.dot_metadata.as_ref() {
         w.write_all(format!("{}\n", metadata).as_bytes())?;
@@ -191,19 +992,163 @@ fn dump_dot<W: Write>(mut w: W, graph: Graph<String>, subargs: &ShowArgs) -> Res
     if let Some(rankdir) = subargs.dot_rankdir.as_ref() {
         w.write_all(format!("    rankdir={};\n\n", dot_quote(rankdir)).as_bytes())?;
     }
-    for (i, n) in graph.values.iter().enumerate() {
-        w.write_all(format!("    n{} [label={}];\n", i, dot_quote(n)).as_bytes())?;
+    let mut depth = subargs.dot_cluster_depth.unwrap_or(usize::MAX);
+    let graph = match subargs.dot_cluster_separator.as_ref() {
+        Some(separator) if subargs.dot_collapse => {
+            // collapsed nodes stay inside their parent clusters
+            depth -= 1;
+            cluster::collapse(&graph, separator, depth + 1)
+        }
+        _ => graph,
+    };
+    match subargs.dot_cluster_separator.as_ref() {
+        Some(separator) => {
+            let mut next_id = 0;
+            let root = cluster::clusters(&graph, separator, depth);
+            dump_dot_cluster(&mut w, &graph, &root, 1, &mut next_id)?;
+        }
+        None => {
+            for i in 0..graph.values.len() {
+                dump_dot_node(&mut w, &graph, i, 1)?;
+            }
+        }
     }
     w.write_all(b"\n")?;
-    for e in graph.to_index_edges() {
-        w.write_all(format!("    n{} -> n{};\n", e.0, e.1).as_bytes())?;
+    let mut kind_styles: HashMap<&str, &str> = DEFAULT_DOT_KIND_STYLES.iter().copied().collect();
+    for style in subargs.dot_kind_style.iter() {
+        let Some((kind, attrs)) = style.split_once('=') else {
+            bail!("can't parse dot kind style: {}", style)
+        };
+        kind_styles.insert(kind, attrs);
+    }
+    for (e, edge_attrs) in graph.to_index_edges_with_attrs() {
+        let mut attrs = Some(edge_attrs)
+            .filter(|a| !a.is_empty())
+            .map(dot_attrs)
+            .into_iter()
+            .collect_vec();
+        attrs.extend(
+            kind_styles
+                .get(edge_kind(edge_attrs))
+                .map(|s| s.to_string()),
+        );
+        if attrs.is_empty() {
+            w.write_all(format!("    n{} -> n{};\n", e.0, e.1).as_bytes())?;
+        } else {
+            w.write_all(format!("    n{} -> n{} [{}];\n", e.0, e.1, attrs.join(", ")).as_bytes())?;
+        }
     }
     w.write_all(b"}\n")?;
     Ok(())
 }
 
+fn dump_svg<W: Write>(mut w: W, graph: Graph<String>, subargs: &DumpArgs) -> Result<()> {
+    let rankdir = subargs.dot_rankdir.as_deref().unwrap_or("TB");
+    let Some(rankdir) = layout::RankDir::parse(rankdir) else {
+        bail!("unknown rankdir: {}", rankdir)
+    };
+    let sizes = graph
+        .values
+        .iter()
+        .map(|v| {
+            (
+                v.chars().count() as f64 * SVG_CHAR_WIDTH + 20.0,
+                SVG_NODE_HEIGHT,
+            )
+        })
+        .collect_vec();
+    let layout = layout::layout(&graph, &sizes, rankdir);
+    let self_loops = graph.find_self_loops();
+    let width = layout.width + if self_loops.is_empty() { 0.0 } else { 30.0 };
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0:.1}" height="{1:.1}" viewBox="0 0 {0:.1} {1:.1}" font-family="sans-serif" font-size="12">"#,
+        width, layout.height
+    )?;
+    writeln!(w, r#"  <defs>"#)?;
+    writeln!(
+        w,
+        r#"    <marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z"/></marker>"#
+    )?;
+    writeln!(w, r#"  </defs>"#)?;
+    for (from, to, points) in layout.edges.iter() {
+        let d = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("{} {:.1} {:.1}", if i == 0 { "M" } else { "L" }, p.0, p.1))
+            .join(" ");
+        writeln!(
+            w,
+            r#"  <path d="{}" fill="none" stroke="black" marker-end="url(#arrow)"><title>{} -> {}</title></path>"#,
+            d,
+            xml_escape(&graph.values[*from]),
+            xml_escape(&graph.values[*to])
+        )?;
+    }
+    for &i in self_loops.iter() {
+        let (x, y) = layout.nodes[i];
+        let x = x + sizes[i].0 / 2.0;
+        writeln!(
+            w,
+            r#"  <path d="M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}" fill="none" stroke="black" marker-end="url(#arrow)"/>"#,
+            x,
+            y - 6.0,
+            x + 25.0,
+            y - 20.0,
+            x + 25.0,
+            y + 20.0,
+            x,
+            y + 6.0
+        )?;
+    }
+    for (i, n) in graph.values.iter().enumerate() {
+        let (x, y) = layout.nodes[i];
+        let (width, height) = sizes[i];
+        writeln!(w, r#"  <g>"#)?;
+        writeln!(
+            w,
+            r#"    <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="4" fill="white" stroke="black"/>"#,
+            x - width / 2.0,
+            y - height / 2.0,
+            width,
+            height
+        )?;
+        writeln!(
+            w,
+            r#"    <text x="{:.1}" y="{:.1}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            x,
+            y,
+            xml_escape(n)
+        )?;
+        writeln!(w, r#"  </g>"#)?;
+    }
+    writeln!(w, "</svg>")?;
+    Ok(())
+}
+
 fn dump_json<W: Write>(mut w: W, graph: Graph<String>) -> Result<()> {
-    serde_json::to_writer(&mut w, &graph.to_btree_map()).context("can't dump json")?;
+    if graph.has_attrs() {
+        let json_graph = JsonGraph {
+            nodes: graph
+                .values
+                .iter()
+                .enumerate()
+                .map(|(i, n)| (n.clone(), attrs_to_json(graph.node_attrs(i))))
+                .collect(),
+            edges: graph
+                .to_index_edges_with_attrs()
+                .iter()
+                .map(|(e, attrs)| JsonEdge {
+                    from: graph.values[e.0].clone(),
+                    to: graph.values[e.1].clone(),
+                    attrs: attrs_to_json(Some(attrs)),
+                })
+                .collect(),
+        };
+        serde_json::to_writer(&mut w, &json_graph).context("can't dump json")?;
+    } else {
+        serde_json::to_writer(&mut w, &graph.to_btree_map()).context("can't dump json")?;
+    }
     w.write_all(b"\n").context("can't dump json")
 }
 
@@ -211,16 +1156,27 @@ fn dump<W: Write>(
     w: W,
     graph: Graph<String>,
     format: OutputFormat,
-    subargs: &ShowArgs,
+    subargs: &DumpArgs,
+    load: &LoadArgs,
 ) -> Result<()> {
     match format {
         OutputFormat::Text => dump_text(w, graph),
         OutputFormat::Json => dump_json(w, graph),
         OutputFormat::Dot => dump_dot(w, graph, subargs),
+        OutputFormat::Adjacency => dump_adjacency(w, graph),
+        OutputFormat::Csv => dump_csv(w, graph, ',', load),
+        OutputFormat::Tsv => dump_csv(w, graph, '\t', load),
+        OutputFormat::Html => html::write_html(w, &graph),
+        OutputFormat::Svg => dump_svg(w, graph, subargs),
     }
 }
 
-fn dump_with_path<P: AsRef<Path>>(p: P, graph: Graph<String>, subargs: &ShowArgs) -> Result<()> {
+fn dump_with_path<P: AsRef<Path>>(
+    p: P,
+    graph: Graph<String>,
+    subargs: &DumpArgs,
+    load: &LoadArgs,
+) -> Result<()> {
     let format = subargs
         .to
         .as_ref()
@@ -230,38 +1186,68 @@ fn dump_with_path<P: AsRef<Path>>(p: P, graph: Graph<String>, subargs: &ShowArgs
     if p.as_ref() == Path::new("-") {
         let stdout_lock = stdout().lock();
         let w = BufWriter::new(stdout_lock);
-        dump(w, graph, format, subargs)
+        dump(w, graph, format, subargs, load)
     } else {
-        let swp = NamedTempFile::new_in(p.as_ref().parent().unwrap())?;
+        let dir = match p.as_ref().parent() {
+            Some(dir) if dir != Path::new("") => dir,
+            _ => Path::new("."),
+        };
+        let swp = NamedTempFile::new_in(dir)?;
         {
             let f = File::create(&swp)?;
             let w = BufWriter::new(f);
-            dump(w, graph, format, subargs)?
+            dump(w, graph, format, subargs, load)?
         }
         swp.persist(p)?;
         Ok(())
     }
 }
 
+fn condense(graph: Graph<String>, enabled: bool) -> Graph<String> {
+    if !enabled {
+        return graph;
+    }
+    let (graph, mapping) = scc::condense(&graph);
+    for (name, members) in mapping.iter() {
+        eprintln!("condensed: {} {}", name, members.join(" "));
+    }
+    graph
+}
+
 fn show(_args: &Args, subargs: &ShowArgs) -> Result<()> {
-    let graph = load_with_path(&subargs.file, subargs.from.clone())?;
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let graph = condense(graph, subargs.condense);
     debug!("{:?}", graph);
-    let output = subargs.output.clone().unwrap_or_else(|| "-".into());
+    let output = subargs.dump.output.clone().unwrap_or_else(|| "-".into());
     if subargs.inverted {
-        dump_with_path(output, graph.invert(), subargs)?;
+        dump_with_path(output, graph.invert(), &subargs.dump, &subargs.load)?;
     } else {
-        dump_with_path(output, graph, subargs)?;
+        dump_with_path(output, graph, &subargs.dump, &subargs.load)?;
     }
     Ok(())
 }
 
 fn dfs(_args: &Args, subargs: &DfsArgs) -> Result<()> {
-    let graph = load_with_path(&subargs.file, subargs.from.clone())?;
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
     let is = match &subargs.start {
         Some(k) => vec![graph.value_to_index[k]],
         None => graph.find_roots(),
     };
-    if subargs.path {
+    if let Some(prefix) = subargs.prefix {
+        let prefix = match prefix {
+            TreePrefix::Indent => tree::Prefix::Indent,
+            TreePrefix::Depth => tree::Prefix::Depth,
+            TreePrefix::None => tree::Prefix::None,
+        };
+        let charset = match subargs.charset {
+            TreeCharset::Utf8 => tree::UTF8,
+            TreeCharset::Ascii => tree::ASCII,
+        };
+        let mut w = BufWriter::new(stdout().lock());
+        for line in tree::tree(&graph, &is, prefix, &charset, subargs.max_depth) {
+            writeln!(w, "{}", line)?;
+        }
+    } else if subargs.path {
         let mut path: Vec<String> = vec![];
         dfs::dfs(&graph, is.as_slice(), |i, t, _f| {
             path.resize(i + 1, "".to_owned());
@@ -290,7 +1276,7 @@ fn dfs(_args: &Args, subargs: &DfsArgs) -> Result<()> {
 }
 
 fn bfs(_args: &Args, subargs: &BfsArgs) -> Result<()> {
-    let graph = load_with_path(&subargs.file, subargs.from.clone())?;
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
     let is = match &subargs.start {
         Some(k) => vec![graph.value_to_index[k]],
         None => graph.find_roots(),
@@ -318,7 +1304,8 @@ fn bfs(_args: &Args, subargs: &BfsArgs) -> Result<()> {
 }
 
 fn tsort(_args: &Args, subargs: &TsortArgs) -> Result<()> {
-    let graph = load_with_path(&subargs.file, subargs.from.clone())?;
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let graph = condense(graph, subargs.condense);
     let result = tsort::tsort(&graph, |t| {
         println!("{}", graph.values[t]);
     });
@@ -336,6 +1323,759 @@ fn tsort(_args: &Args, subargs: &TsortArgs) -> Result<()> {
     Ok(())
 }
 
+fn load_weights(p: &Path) -> Result<HashMap<String, f64>> {
+    let f = File::open(p).with_context(|| format!("can't open {}", p.display()))?;
+    let mut weights = HashMap::new();
+    for (i, line) in BufReader::new(f).lines().enumerate() {
+        let line = line.context("can't load weights")?;
+        if line.is_empty() {
+            continue;
+        }
+        let Some((n, w)) = line.rsplit_once(' ') else {
+            bail!("can't load weights: line {}: expected 2 fields", i + 1)
+        };
+        let w: f64 = w
+            .parse()
+            .with_context(|| format!("can't load weights: line {}: bad weight", i + 1))?;
+        weights.insert(n.to_owned(), w);
+    }
+    Ok(weights)
+}
+
+fn critical_path(_args: &Args, subargs: &CriticalPathArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let side_weights = match &subargs.weights {
+        Some(p) => Some(load_weights(p)?),
+        None => None,
+    };
+    let mut weights = vec![0.0; graph.values.len()];
+    for (i, n) in graph.values.iter().enumerate() {
+        let w = match &side_weights {
+            Some(side_weights) => side_weights.get(n).copied(),
+            None => match graph
+                .node_attrs(i)
+                .and_then(|attrs| attrs.get(&subargs.weight_attr))
+            {
+                Some(w) => Some(
+                    w.parse()
+                        .with_context(|| format!("bad weight of {}: {}", n, w))?,
+                ),
+                None => None,
+            },
+        };
+        match w {
+            Some(w) => weights[i] = w,
+            None => debug!("no weight: {}", n),
+        }
+    }
+    let result = match critical_path::critical_path(&graph, &weights) {
+        Ok(result) => result,
+        Err(remaining) => {
+            warn!("contains a loop");
+            for v in remaining.iter().sorted() {
+                eprintln!("loop: {}", graph.values[*v]);
+            }
+            exit(1);
+        }
+    };
+    let path: Vec<&str> = result
+        .path
+        .iter()
+        .map(|i| graph.values[*i].as_str())
+        .collect();
+    println!("path: {}", path.join(" "));
+    println!("cost: {}", result.cost);
+    for (i, slack) in result.slacks {
+        println!("{} {}", graph.values[i], slack);
+    }
+    Ok(())
+}
+
+fn shortest_path(_args: &Args, subargs: &ShortestPathArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let index = |k: &String| {
+        graph
+            .value_to_index
+            .get(k)
+            .copied()
+            .with_context(|| format!("no such node: {}", k))
+    };
+    let (start, end) = (index(&subargs.start)?, index(&subargs.end)?);
+    // parallel edges cost as much as the cheapest of them
+    let mut weights: HashMap<(usize, usize), f64> = HashMap::new();
+    for (e, attrs) in graph.to_index_edges_with_attrs() {
+        let w = match attrs.get(&subargs.weight_attr) {
+            Some(w) => {
+                let edge = format!("{} {}", graph.values[e.0], graph.values[e.1]);
+                let w: f64 = w
+                    .parse()
+                    .with_context(|| format!("bad weight of {}: {}", edge, w))?;
+                if w.is_nan() || w < 0.0 {
+                    bail!("bad weight of {}: {}", edge, w);
+                }
+                w
+            }
+            None => 1.0,
+        };
+        let min = weights.entry((e.0, e.1)).or_insert(w);
+        *min = min.min(w);
+    }
+    let Some((path, cost)) =
+        dijkstra::shortest_path(&graph, start, end, |from, to| weights[&(from, to)])
+    else {
+        bail!("no path from {} to {}", subargs.start, subargs.end)
+    };
+    let path: Vec<&str> = path.iter().map(|i| graph.values[*i].as_str()).collect();
+    match subargs.to {
+        ReportFormat::Text => {
+            println!("path: {}", path.join(" "));
+            println!("cost: {}", cost);
+        }
+        ReportFormat::Json => {
+            println!("{}", serde_json::json!({ "path": path, "cost": cost }));
+        }
+    }
+    Ok(())
+}
+
+fn dump_diff_text<W: Write>(mut w: W, d: &GraphDiff<String>, subargs: &DiffArgs) -> Result<()> {
+    writeln!(w, "--- {}", subargs.old.display())?;
+    writeln!(w, "+++ {}", subargs.new.display())?;
+    if !d.removed_nodes.is_empty() || !d.added_nodes.is_empty() {
+        writeln!(w, "@@ nodes @@")?;
+        for n in d.removed_nodes.iter() {
+            writeln!(w, "-{}", n)?;
+        }
+        for n in d.added_nodes.iter() {
+            writeln!(w, "+{}", n)?;
+        }
+    }
+    if !d.removed_edges.is_empty() || !d.added_edges.is_empty() {
+        writeln!(w, "@@ edges @@")?;
+        for e in d.removed_edges.iter() {
+            writeln!(w, "-{} {}", e.0, e.1)?;
+        }
+        for e in d.added_edges.iter() {
+            writeln!(w, "+{} {}", e.0, e.1)?;
+        }
+    }
+    for (root, introduced) in d.new_transitive.iter() {
+        writeln!(w, "@@ transitive {} @@", root)?;
+        for n in introduced.iter() {
+            writeln!(w, "+{}", n)?;
+        }
+    }
+    if !d.new_cycles.is_empty() {
+        writeln!(w, "@@ cycles @@")?;
+        for c in d.new_cycles.iter() {
+            writeln!(w, "+{}", c.join(" "))?;
+        }
+    }
+    Ok(())
+}
+
+fn dump_diff_json<W: Write>(mut w: W, d: &GraphDiff<String>) -> Result<()> {
+    let edges = |es: &[Edge<String>]| es.iter().map(|e| [e.0.clone(), e.1.clone()]).collect_vec();
+    let json = serde_json::json!({
+        "removed_nodes": d.removed_nodes,
+        "added_nodes": d.added_nodes,
+        "removed_edges": edges(&d.removed_edges),
+        "added_edges": edges(&d.added_edges),
+        "new_transitive": d.new_transitive.iter().cloned().collect::<BTreeMap<_, _>>(),
+        "new_cycles": d.new_cycles,
+    });
+    serde_json::to_writer(&mut w, &json).context("can't dump json")?;
+    w.write_all(b"\n").context("can't dump json")
+}
+
+fn dump_diff_dot<W: Write>(
+    mut w: W,
+    old: &Graph<String>,
+    new: &Graph<String>,
+    d: &GraphDiff<String>,
+) -> Result<()> {
+    let mut union = old.clone();
+    for e in new.to_edges() {
+        union.add_edge(&e);
+    }
+    for n in new.values.iter() {
+        union.add_node(n);
+    }
+    let color = |removed: bool, added: bool| match (removed, added) {
+        (true, _) => Some("red"),
+        (_, true) => Some("green"),
+        _ => None,
+    };
+    let removed_edges: HashSet<(&String, &String)> =
+        d.removed_edges.iter().map(|e| (&e.0, &e.1)).collect();
+    let added_edges: HashSet<(&String, &String)> =
+        d.added_edges.iter().map(|e| (&e.0, &e.1)).collect();
+    w.write_all(b"digraph {\n")?;
+    for (i, n) in union.values.iter().enumerate() {
+        match color(d.removed_nodes.contains(n), d.added_nodes.contains(n)) {
+            Some(c) => writeln!(w, "    n{} [label={}, color={}];", i, dot_quote(n), c)?,
+            None => writeln!(w, "    n{} [label={}];", i, dot_quote(n))?,
+        }
+    }
+    w.write_all(b"\n")?;
+    let mut seen = HashSet::new();
+    for e in union.to_index_edges() {
+        if !seen.insert((e.0, e.1)) {
+            continue;
+        }
+        let key = (&union.values[e.0], &union.values[e.1]);
+        match color(removed_edges.contains(&key), added_edges.contains(&key)) {
+            Some(c) => writeln!(w, "    n{} -> n{} [color={}];", e.0, e.1, c)?,
+            None => writeln!(w, "    n{} -> n{};", e.0, e.1)?,
+        }
+    }
+    w.write_all(b"}\n")?;
+    Ok(())
+}
+
+fn diff(_args: &Args, subargs: &DiffArgs) -> Result<()> {
+    if subargs.old == Path::new("-") && subargs.new == Path::new("-") {
+        bail!("stdin (-) is given more than once");
+    }
+    let old = load_with_path(&subargs.old, subargs.from.clone(), &subargs.load)?;
+    let new = load_with_path(&subargs.new, subargs.from.clone(), &subargs.load)?;
+    let d = diff::diff(&old, &new);
+    let w = BufWriter::new(stdout().lock());
+    match subargs.to {
+        DiffFormat::Text => dump_diff_text(w, &d, subargs)?,
+        DiffFormat::Json => dump_diff_json(w, &d)?,
+        DiffFormat::Dot => dump_diff_dot(w, &old, &new, &d)?,
+    }
+    if !d.is_empty() {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn dump_check_text<W: Write>(
+    mut w: W,
+    graph: &Graph<String>,
+    violations: &[check::Violation],
+) -> Result<()> {
+    for v in violations.iter() {
+        let path = v
+            .path
+            .iter()
+            .map(|i| graph.values[*i].as_str())
+            .join(" -> ");
+        writeln!(w, "{}: {}: {}", v.rule, v.message, path)?;
+    }
+    Ok(())
+}
+
+fn dump_check_junit<W: Write>(
+    mut w: W,
+    graph: &Graph<String>,
+    violations: &[check::Violation],
+) -> Result<()> {
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<testsuites name="depq" tests="{}" failures="{}">"#,
+        check::RULE_IDS.len(),
+        check::RULE_IDS
+            .iter()
+            .filter(|id| violations.iter().any(|v| v.rule == **id))
+            .count()
+    )?;
+    writeln!(
+        w,
+        r#"  <testsuite name="check" tests="{}">"#,
+        check::RULE_IDS.len()
+    )?;
+    for id in check::RULE_IDS.iter() {
+        let vs: Vec<_> = violations.iter().filter(|v| v.rule == *id).collect();
+        if vs.is_empty() {
+            writeln!(w, r#"    <testcase name="{}" classname="depq.check"/>"#, id)?;
+            continue;
+        }
+        writeln!(w, r#"    <testcase name="{}" classname="depq.check">"#, id)?;
+        let message = format!("{} violation(s)", vs.len());
+        writeln!(w, r#"      <failure message="{}">"#, xml_escape(&message))?;
+        for v in vs {
+            let path = v
+                .path
+                .iter()
+                .map(|i| graph.values[*i].as_str())
+                .join(" -> ");
+            writeln!(w, "{}", xml_escape(&format!("{}: {}", v.message, path)))?;
+        }
+        writeln!(w, "      </failure>")?;
+        writeln!(w, "    </testcase>")?;
+    }
+    writeln!(w, "  </testsuite>")?;
+    writeln!(w, "</testsuites>")?;
+    Ok(())
+}
+
+fn dump_check_sarif<W: Write>(
+    mut w: W,
+    graph: &Graph<String>,
+    violations: &[check::Violation],
+    subargs: &CheckArgs,
+) -> Result<()> {
+    // stdin has no uri, and nodes of merged files can't be traced back to a single file
+    let uris = subargs
+        .files
+        .iter()
+        .filter(|p| *p != Path::new("-"))
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .collect_vec();
+    let artifacts = uris
+        .iter()
+        .map(|uri| serde_json::json!({ "location": { "uri": uri } }))
+        .collect_vec();
+    let results = violations
+        .iter()
+        .map(|v| {
+            let path = v
+                .path
+                .iter()
+                .map(|i| graph.values[*i].as_str())
+                .join(" -> ");
+            let mut location = serde_json::json!({
+                "logicalLocations": v
+                    .path
+                    .iter()
+                    .map(|i| serde_json::json!({ "name": graph.values[*i] }))
+                    .collect_vec(),
+            });
+            if let [uri] = uris.as_slice() {
+                if subargs.files.len() == 1 {
+                    location["physicalLocation"] =
+                        serde_json::json!({ "artifactLocation": { "uri": uri, "index": 0 } });
+                }
+            }
+            serde_json::json!({
+                "ruleId": v.rule,
+                "level": "error",
+                "message": { "text": format!("{}: {}", v.message, path) },
+                "locations": [location],
+            })
+        })
+        .collect_vec();
+    let rules = check::RULE_IDS
+        .iter()
+        .map(|id| serde_json::json!({ "id": id }))
+        .collect_vec();
+    let sarif = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": "depq", "rules": rules } },
+            "artifacts": artifacts,
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut w, &sarif).context("can't dump sarif")?;
+    w.write_all(b"\n").context("can't dump sarif")
+}
+
+fn load_rules(p: &Path) -> Result<check::Rules> {
+    let f = File::open(p).with_context(|| format!("can't open {}", p.display()))?;
+    serde_json::from_reader(BufReader::new(f)).context("can't load rules")
+}
+
+fn check(_args: &Args, subargs: &CheckArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let rules = load_rules(&subargs.rules)?;
+    let violations = check::check(&graph, &rules)?;
+    let w = BufWriter::new(stdout().lock());
+    match subargs.to {
+        CheckFormat::Text => dump_check_text(w, &graph, &violations)?,
+        CheckFormat::Junit => dump_check_junit(w, &graph, &violations)?,
+        CheckFormat::Sarif => dump_check_sarif(w, &graph, &violations, subargs)?,
+    }
+    if !violations.is_empty() {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn dump_layers_json<W: Write>(
+    mut w: W,
+    graph: &Graph<String>,
+    layers: &[check::LayerRule],
+    assigned: &[Option<usize>],
+    violations: &[check::Violation],
+) -> Result<()> {
+    let nodes: BTreeMap<&str, Option<&str>> = graph
+        .values
+        .iter()
+        .zip(assigned.iter())
+        .map(|(n, l)| (n.as_str(), l.map(|l| layers[l].name.as_str())))
+        .collect();
+    let violations = violations
+        .iter()
+        .map(|v| {
+            let path = v
+                .path
+                .iter()
+                .map(|i| graph.values[*i].as_str())
+                .collect_vec();
+            serde_json::json!({ "message": v.message, "path": path })
+        })
+        .collect_vec();
+    let json = serde_json::json!({ "nodes": nodes, "violations": violations });
+    serde_json::to_writer(&mut w, &json).context("can't dump json")?;
+    w.write_all(b"\n").context("can't dump json")
+}
+
+fn dump_layers_dot<W: Write>(
+    mut w: W,
+    graph: &Graph<String>,
+    layers: &[check::LayerRule],
+    assigned: &[Option<usize>],
+    violations: &[check::Violation],
+) -> Result<()> {
+    w.write_all(b"digraph {\n")?;
+    for (l, layer) in layers.iter().enumerate() {
+        writeln!(w, "    subgraph cluster_{} {{", l)?;
+        writeln!(w, "        label={};", dot_quote(&layer.name))?;
+        for (i, n) in graph.values.iter().enumerate() {
+            if assigned[i] == Some(l) {
+                writeln!(w, "        n{} [label={}];", i, dot_quote(n))?;
+            }
+        }
+        writeln!(w, "    }}")?;
+    }
+    for (i, n) in graph.values.iter().enumerate() {
+        if assigned[i].is_none() {
+            writeln!(w, "    n{} [label={}];", i, dot_quote(n))?;
+        }
+    }
+    w.write_all(b"\n")?;
+    let violated: HashSet<(usize, usize)> = violations
+        .iter()
+        .filter_map(|v| v.path.iter().rev().take(2).collect_tuple())
+        .map(|(t, f)| (*f, *t))
+        .collect();
+    for e in graph.to_index_edges() {
+        if violated.contains(&(e.0, e.1)) {
+            writeln!(w, "    n{} -> n{} [color=red];", e.0, e.1)?;
+        } else {
+            writeln!(w, "    n{} -> n{};", e.0, e.1)?;
+        }
+    }
+    w.write_all(b"}\n")?;
+    Ok(())
+}
+
+fn layers(_args: &Args, subargs: &LayersArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let rules = load_rules(&subargs.rules)?;
+    if rules.layers.is_empty() {
+        bail!("no layers in {}", subargs.rules.display());
+    }
+    let assigned = check::assign_layers(&graph, &rules.layers)?;
+    let violations = check::layer_violations(&graph, &rules.layers)?;
+    let w = BufWriter::new(stdout().lock());
+    match subargs.to {
+        LayersFormat::Text => dump_check_text(w, &graph, &violations)?,
+        LayersFormat::Json => dump_layers_json(w, &graph, &rules.layers, &assigned, &violations)?,
+        LayersFormat::Dot => dump_layers_dot(w, &graph, &rules.layers, &assigned, &violations)?,
+    }
+    if !violations.is_empty() {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn stats(_args: &Args, subargs: &StatsArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let st = stats::stats(&graph, subargs.top);
+    let names = |top: &[(usize, usize)]| {
+        top.iter()
+            .map(|(i, d)| (graph.values[*i].as_str(), *d))
+            .collect_vec()
+    };
+    match subargs.to {
+        ReportFormat::Text => {
+            println!("nodes: {}", st.nodes);
+            println!("edges: {}", st.edges);
+            println!("roots: {}", st.roots);
+            println!("leaves: {}", st.leaves);
+            println!("max in-degree: {}", st.max_in_degree);
+            println!("max out-degree: {}", st.max_out_degree);
+            println!("avg in-degree: {:.2}", st.avg_in_degree);
+            println!("avg out-degree: {:.2}", st.avg_out_degree);
+            println!("longest path: {}", st.longest_path);
+            println!("sccs: {}", st.sccs);
+            println!("largest scc: {}", st.largest_scc);
+            println!("wccs: {}", st.wccs);
+            println!("top fan-in:");
+            for (n, d) in names(&st.top_fan_in) {
+                println!("    {} {}", n, d);
+            }
+            println!("top fan-out:");
+            for (n, d) in names(&st.top_fan_out) {
+                println!("    {} {}", n, d);
+            }
+        }
+        ReportFormat::Json => {
+            let top = |top: &[(usize, usize)]| {
+                names(top)
+                    .into_iter()
+                    .map(|(n, d)| serde_json::json!({ "name": n, "degree": d }))
+                    .collect_vec()
+            };
+            let json = serde_json::json!({
+                "nodes": st.nodes,
+                "edges": st.edges,
+                "roots": st.roots,
+                "leaves": st.leaves,
+                "max_in_degree": st.max_in_degree,
+                "max_out_degree": st.max_out_degree,
+                "avg_in_degree": st.avg_in_degree,
+                "avg_out_degree": st.avg_out_degree,
+                "longest_path": st.longest_path,
+                "sccs": st.sccs,
+                "largest_scc": st.largest_scc,
+                "wccs": st.wccs,
+                "top_fan_in": top(&st.top_fan_in),
+                "top_fan_out": top(&st.top_fan_out),
+            });
+            println!("{}", json);
+        }
+    }
+    Ok(())
+}
+
+fn rank(_args: &Args, subargs: &RankArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let mut ranks = rank::rank(&graph, subargs.damping);
+    ranks.sort_by(|a, b| {
+        let ord = match subargs.sort {
+            RankKey::Pagerank => b.pagerank.total_cmp(&a.pagerank),
+            RankKey::Betweenness => b.betweenness.total_cmp(&a.betweenness),
+            RankKey::Dependents => b.dependents.cmp(&a.dependents),
+        };
+        ord.then(a.node.cmp(&b.node))
+    });
+    if let Some(top) = subargs.top {
+        ranks.truncate(top);
+    }
+    let mut w = BufWriter::new(stdout().lock());
+    match subargs.to {
+        RankFormat::Text => {
+            let width = ranks
+                .iter()
+                .map(|r| graph.values[r.node].len())
+                .fold(4, usize::max);
+            writeln!(
+                w,
+                "{:<width$} {:>10} {:>11} {:>10}",
+                "name", "pagerank", "betweenness", "dependents"
+            )?;
+            for r in ranks.iter() {
+                writeln!(
+                    w,
+                    "{:<width$} {:>10.6} {:>11.2} {:>10}",
+                    graph.values[r.node], r.pagerank, r.betweenness, r.dependents
+                )?;
+            }
+        }
+        RankFormat::Json => {
+            let json = ranks
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "name": graph.values[r.node],
+                        "pagerank": r.pagerank,
+                        "betweenness": r.betweenness,
+                        "dependents": r.dependents,
+                    })
+                })
+                .collect_vec();
+            serde_json::to_writer(&mut w, &json).context("can't dump json")?;
+            w.write_all(b"\n").context("can't dump json")?;
+        }
+        RankFormat::Csv => {
+            csv::write_record(
+                &mut w,
+                &["name", "pagerank", "betweenness", "dependents"],
+                ',',
+            )?;
+            for r in ranks.iter() {
+                let record = [
+                    graph.values[r.node].clone(),
+                    r.pagerank.to_string(),
+                    r.betweenness.to_string(),
+                    r.dependents.to_string(),
+                ];
+                csv::write_record(&mut w, &record, ',')?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn dominators(_args: &Args, subargs: &DominatorsArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let start = graph
+        .value_to_index
+        .get(&subargs.start)
+        .copied()
+        .with_context(|| format!("no such node: {}", subargs.start))?;
+    let idom = dominator::dominators(&graph, start);
+    let tree: Graph<String> = idom
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| d.map(|d| Edge(graph.values[d].clone(), graph.values[i].clone())))
+        .collect();
+    let mut w = BufWriter::new(stdout().lock());
+    match subargs.to {
+        DominatorsFormat::Text => {
+            let mut tree = tree;
+            let start = tree.add_node(&subargs.start);
+            // stop at the first write error and report it
+            let mut result = Ok(());
+            dfs::dfs(&tree, &[start], |i, t, _f| {
+                result = writeln!(w, "{}* {}", " ".repeat(i * 4), tree.values[t]);
+                result.is_ok()
+            });
+            result?;
+        }
+        DominatorsFormat::Json => {
+            let json: BTreeMap<&str, Option<&str>> = idom
+                .iter()
+                .enumerate()
+                .filter(|(i, d)| *i == start || d.is_some())
+                .map(|(i, d)| {
+                    (
+                        graph.values[i].as_str(),
+                        d.map(|d| graph.values[d].as_str()),
+                    )
+                })
+                .collect();
+            serde_json::to_writer(&mut w, &json).context("can't dump json")?;
+            w.write_all(b"\n").context("can't dump json")?;
+        }
+        DominatorsFormat::Dot => {
+            w.write_all(b"digraph {\n")?;
+            for (i, n) in graph.values.iter().enumerate() {
+                if i == start || idom[i].is_some() {
+                    writeln!(w, "    n{} [label={}];", i, dot_quote(n))?;
+                }
+            }
+            w.write_all(b"\n")?;
+            for (i, d) in idom.iter().enumerate() {
+                let Some(d) = d else {
+                    continue;
+                };
+                writeln!(w, "    n{} -> n{};", d, i)?;
+            }
+            w.write_all(b"}\n")?;
+        }
+    }
+    w.flush()?;
+    Ok(())
+}
+
+fn components(_args: &Args, subargs: &ComponentsArgs) -> Result<()> {
+    let mut graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let components = wcc::wcc(&graph);
+    if let Some(split) = subargs.split.as_ref() {
+        if !split.contains("{}") {
+            bail!("split path must contain {{}}: {}", split);
+        }
+        for (c, members) in components.iter().enumerate() {
+            let members: HashSet<usize> = members.iter().copied().collect();
+            let component = graph.filter_nodes(|i| members.contains(&i));
+            let p = split.replace("{}", &c.to_string());
+            dump_with_path(p, component, &subargs.dump, &subargs.load)?;
+        }
+    } else if subargs.attr {
+        for (c, members) in components.iter().enumerate() {
+            for &i in members.iter() {
+                graph.add_node_attrs(i, Attrs::from([(COMPONENT_ATTR.to_owned(), c.to_string())]));
+            }
+        }
+        let output = subargs.dump.output.clone().unwrap_or_else(|| "-".into());
+        dump_with_path(output, graph, &subargs.dump, &subargs.load)?;
+    } else {
+        let mut w = BufWriter::new(stdout().lock());
+        for members in components.iter() {
+            writeln!(
+                w,
+                "{}",
+                members.iter().map(|i| graph.values[*i].as_str()).join(" ")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn break_cycles(_args: &Args, subargs: &BreakCyclesArgs) -> Result<()> {
+    let graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    let removed: HashSet<(usize, usize)> = feedback::feedback_arc_set(&graph)
+        .into_iter()
+        .map(|e| (e.0, e.1))
+        .collect();
+    if subargs.graph {
+        for e in graph.to_index_edges() {
+            if removed.contains(&(e.0, e.1)) {
+                eprintln!("removed: {} {}", graph.values[e.0], graph.values[e.1]);
+            }
+        }
+        let acyclic = graph.filter_edges(|from, to, _| !removed.contains(&(from, to)));
+        let dump = DumpArgs {
+            to: subargs.to.clone(),
+            ..Default::default()
+        };
+        let output = subargs.output.clone().unwrap_or_else(|| "-".into());
+        dump_with_path(output, acyclic, &dump, &subargs.load)?;
+    } else {
+        let mut w = BufWriter::new(stdout().lock());
+        for e in graph.to_index_edges() {
+            if removed.contains(&(e.0, e.1)) {
+                writeln!(w, "{} {}", graph.values[e.0], graph.values[e.1])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn draw(_args: &Args, subargs: &DrawArgs) -> Result<()> {
+    let mut graph = load_with_paths(&subargs.files, subargs.from.clone(), &subargs.load)?;
+    if let Some(start) = subargs.start.as_ref() {
+        let start = graph
+            .value_to_index
+            .get(start)
+            .copied()
+            .with_context(|| format!("no such node: {}", start))?;
+        let reachable = graph.reachable(start);
+        graph = graph.filter_nodes(|i| i == start || reachable.contains(&i));
+    }
+    let charset = if subargs.ascii {
+        term::ASCII
+    } else {
+        term::UNICODE
+    };
+    let color = match subargs.color {
+        ColorMode::Auto => stdout().is_terminal(),
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    };
+    let mut w = BufWriter::new(stdout().lock());
+    for line in term::draw(&graph, &charset, color) {
+        writeln!(w, "{}", line)?;
+    }
+    Ok(())
+}
+
 fn main() -> Result<()> {
     env_logger::init();
     let args = Args::parse();
@@ -345,5 +2085,16 @@ fn main() -> Result<()> {
         Subcommand::Dfs(subargs) => dfs(&args, subargs),
         Subcommand::Bfs(subargs) => bfs(&args, subargs),
         Subcommand::Tsort(subargs) => tsort(&args, subargs),
+        Subcommand::CriticalPath(subargs) => critical_path(&args, subargs),
+        Subcommand::ShortestPath(subargs) => shortest_path(&args, subargs),
+        Subcommand::Diff(subargs) => diff(&args, subargs),
+        Subcommand::Check(subargs) => check(&args, subargs),
+        Subcommand::Layers(subargs) => layers(&args, subargs),
+        Subcommand::Stats(subargs) => stats(&args, subargs),
+        Subcommand::Rank(subargs) => rank(&args, subargs),
+        Subcommand::Dominators(subargs) => dominators(&args, subargs),
+        Subcommand::Components(subargs) => components(&args, subargs),
+        Subcommand::BreakCycles(subargs) => break_cycles(&args, subargs),
+        Subcommand::Draw(subargs) => draw(&args, subargs),
     }
 }
@@ -0,0 +1,256 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    iter::Peekable,
+    path::{Path, PathBuf},
+    str::Chars,
+};
+
+use anyhow::{bail, Context as _, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum InputKind {
+    Explicit,
+    Implicit,
+    OrderOnly,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BuildEdge {
+    pub(crate) target: String,
+    pub(crate) input: String,
+    pub(crate) kind: InputKind,
+    pub(crate) rule: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Path(String),
+    Colon,
+    Pipe,
+    PipePipe,
+    PipeAt,
+}
+
+fn read_var_name(cs: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(&c) = cs.peek() {
+        if !(c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            break;
+        }
+        name.push(c);
+        cs.next();
+    }
+    name
+}
+
+fn read_escape(cs: &mut Peekable<Chars>, vars: &HashMap<String, String>) -> Result<String> {
+    match cs.peek().copied() {
+        Some(c @ (' ' | ':' | '$')) => {
+            cs.next();
+            Ok(c.to_string())
+        }
+        Some('{') => {
+            cs.next();
+            let name: String = cs.by_ref().take_while(|&c| c != '}').collect();
+            Ok(vars.get(&name).cloned().unwrap_or_default())
+        }
+        Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-' => {
+            let name = read_var_name(cs);
+            Ok(vars.get(&name).cloned().unwrap_or_default())
+        }
+        c => bail!("bad $-escape: {:?}", c),
+    }
+}
+
+fn expand(s: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut result = String::new();
+    let mut cs = s.chars().peekable();
+    while let Some(c) = cs.next() {
+        if c == '$' {
+            result.push_str(&read_escape(&mut cs, vars)?);
+        } else {
+            result.push(c);
+        }
+    }
+    Ok(result)
+}
+
+fn tokenize(s: &str, vars: &HashMap<String, String>) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut path = String::new();
+    let mut cs = s.chars().peekable();
+    let flush = |path: &mut String, tokens: &mut Vec<Token>| {
+        if !path.is_empty() {
+            tokens.push(Token::Path(std::mem::take(path)));
+        }
+    };
+    while let Some(c) = cs.next() {
+        match c {
+            ' ' | '\t' => flush(&mut path, &mut tokens),
+            ':' => {
+                flush(&mut path, &mut tokens);
+                tokens.push(Token::Colon);
+            }
+            '|' => {
+                flush(&mut path, &mut tokens);
+                match cs.peek() {
+                    Some('|') => {
+                        cs.next();
+                        tokens.push(Token::PipePipe);
+                    }
+                    Some('@') => {
+                        cs.next();
+                        tokens.push(Token::PipeAt);
+                    }
+                    _ => tokens.push(Token::Pipe),
+                }
+            }
+            '$' => path.push_str(&read_escape(&mut cs, vars)?),
+            c => path.push(c),
+        }
+    }
+    flush(&mut path, &mut tokens);
+    Ok(tokens)
+}
+
+fn parse_build(s: &str, vars: &HashMap<String, String>) -> Result<Vec<BuildEdge>> {
+    let tokens = tokenize(s, vars)?;
+    let Some(colon) = tokens.iter().position(|t| *t == Token::Colon) else {
+        bail!("expected ':'")
+    };
+    let outputs: Vec<&String> = tokens[..colon]
+        .iter()
+        .filter_map(|t| match t {
+            Token::Path(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+    let mut rest = tokens[colon + 1..].iter();
+    let Some(Token::Path(rule)) = rest.next() else {
+        bail!("expected rule name")
+    };
+    let mut inputs: Vec<(&String, InputKind)> = vec![];
+    let mut kind = Some(InputKind::Explicit);
+    for t in rest {
+        match t {
+            Token::Path(p) => {
+                if let Some(kind) = kind {
+                    inputs.push((p, kind));
+                }
+            }
+            Token::Pipe => kind = Some(InputKind::Implicit),
+            Token::PipePipe => kind = Some(InputKind::OrderOnly),
+            Token::PipeAt => kind = None,
+            Token::Colon => bail!("unexpected ':'"),
+        }
+    }
+    let mut edges = vec![];
+    for target in outputs.iter() {
+        for (input, kind) in inputs.iter() {
+            edges.push(BuildEdge {
+                target: (*target).clone(),
+                input: (*input).clone(),
+                kind: *kind,
+                rule: rule.clone(),
+            });
+        }
+    }
+    Ok(edges)
+}
+
+fn logical_lines<R: BufRead>(r: R) -> Result<Vec<String>> {
+    let mut lines = vec![];
+    let mut buf: Option<String> = None;
+    for line in r.lines() {
+        let line = line?;
+        let line = match buf.take() {
+            Some(mut prev) => {
+                prev.push_str(line.trim_start());
+                prev
+            }
+            None => line,
+        };
+        let trailing_dollars = line.chars().rev().take_while(|&c| c == '$').count();
+        if trailing_dollars % 2 == 1 {
+            buf = Some(line[..line.len() - 1].to_owned());
+        } else {
+            lines.push(line);
+        }
+    }
+    lines.extend(buf);
+    Ok(lines)
+}
+
+struct Parser {
+    // paths of include and subninja statements are relative to the directory of the top-level file,
+    // like ninja running there
+    dir: PathBuf,
+    files: Vec<PathBuf>,
+    edges: Vec<BuildEdge>,
+}
+
+impl Parser {
+    fn parse_file(&mut self, path: &str, vars: &mut HashMap<String, String>) -> Result<()> {
+        let p = self.dir.join(path);
+        if self.files.contains(&p) {
+            bail!("recursive include: {}", p.display());
+        }
+        let f = File::open(&p).with_context(|| format!("can't open {}", p.display()))?;
+        self.files.push(p.clone());
+        self.parse(BufReader::new(f), vars)
+            .with_context(|| format!("in {}", p.display()))?;
+        self.files.pop();
+        Ok(())
+    }
+
+    fn parse<R: BufRead>(&mut self, r: R, vars: &mut HashMap<String, String>) -> Result<()> {
+        for (i, line) in logical_lines(r)?.iter().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if line.starts_with([' ', '\t']) {
+                // bindings scoped to a build, rule or pool
+                continue;
+            }
+            let (keyword, rest) = line.split_once([' ', '\t']).unwrap_or((line, ""));
+            match keyword {
+                "build" => self.edges.extend(
+                    parse_build(rest, vars)
+                        .with_context(|| format!("line {}: bad build", i + 1))?,
+                ),
+                "rule" | "pool" | "default" => {}
+                "include" | "subninja" => {
+                    let path = expand(rest.trim(), vars)
+                        .with_context(|| format!("line {}: bad path", i + 1))?;
+                    if keyword == "include" {
+                        self.parse_file(&path, vars)?;
+                    } else {
+                        // a subninja sees the variables of its parent but can't change them
+                        self.parse_file(&path, &mut vars.clone())?;
+                    }
+                }
+                _ => {
+                    let Some((name, value)) = line.split_once('=') else {
+                        bail!("line {}: unexpected: {}", i + 1, line)
+                    };
+                    let value = expand(value.trim_start(), vars)
+                        .with_context(|| format!("line {}: bad value", i + 1))?;
+                    vars.insert(name.trim().to_owned(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn parse<R: BufRead>(r: R, dir: &Path) -> Result<Vec<BuildEdge>> {
+    let mut parser = Parser {
+        dir: dir.to_path_buf(),
+        files: vec![],
+        edges: vec![],
+    };
+    parser.parse(r, &mut HashMap::new())?;
+    Ok(parser.edges)
+}
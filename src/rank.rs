@@ -0,0 +1,109 @@
+use std::{collections::VecDeque, hash::Hash};
+
+use crate::graph::Graph;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Rank {
+    pub(crate) node: usize,
+    pub(crate) pagerank: f64,
+    pub(crate) betweenness: f64,
+    pub(crate) dependents: usize,
+}
+
+const PAGERANK_ITERATIONS: usize = 100;
+const PAGERANK_EPSILON: f64 = 1e-10;
+
+pub(crate) fn pagerank<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>, damping: f64) -> Vec<f64> {
+    let n = graph.values.len();
+    if n == 0 {
+        return vec![];
+    }
+    let mut ranks = vec![1.0 / n as f64; n];
+    for _ in 0..PAGERANK_ITERATIONS {
+        let mut next = vec![(1.0 - damping) / n as f64; n];
+        let mut dangling = 0.0;
+        for (i, rank) in ranks.iter().enumerate() {
+            match graph.deps.get(&i).filter(|vs| !vs.is_empty()) {
+                Some(vs) => {
+                    for &t in vs.iter() {
+                        next[t] += damping * rank / vs.len() as f64;
+                    }
+                }
+                None => dangling += damping * rank,
+            }
+        }
+        for r in next.iter_mut() {
+            *r += dangling / n as f64;
+        }
+        let delta: f64 = next
+            .iter()
+            .zip(ranks.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        ranks = next;
+        if delta < PAGERANK_EPSILON {
+            break;
+        }
+    }
+    ranks
+}
+
+// Brandes' algorithm on the unweighted directed graph
+pub(crate) fn betweenness<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<f64> {
+    let n = graph.values.len();
+    let mut centrality = vec![0.0; n];
+    for s in 0..n {
+        let mut stack = vec![];
+        let mut preds: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut sigma = vec![0.0; n];
+        let mut dist: Vec<Option<usize>> = vec![None; n];
+        sigma[s] = 1.0;
+        dist[s] = Some(0);
+        let mut queue = VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            let d = dist[v].unwrap_or_default();
+            for &w in graph.deps.get(&v).into_iter().flatten() {
+                if dist[w].is_none() {
+                    dist[w] = Some(d + 1);
+                    queue.push_back(w);
+                }
+                if dist[w] == Some(d + 1) {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+        let mut delta = vec![0.0; n];
+        while let Some(w) = stack.pop() {
+            for &v in preds[w].iter() {
+                delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+            }
+            if w != s {
+                centrality[w] += delta[w];
+            }
+        }
+    }
+    centrality
+}
+
+pub(crate) fn rank<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>, damping: f64) -> Vec<Rank> {
+    let pageranks = pagerank(graph, damping);
+    let betweennesses = betweenness(graph);
+    let mut dependents = vec![0; graph.values.len()];
+    for j in 0..graph.values.len() {
+        for t in graph.reachable(j) {
+            if t != j {
+                dependents[t] += 1;
+            }
+        }
+    }
+    (0..graph.values.len())
+        .map(|i| Rank {
+            node: i,
+            pagerank: pageranks[i],
+            betweenness: betweennesses[i],
+            dependents: dependents[i],
+        })
+        .collect()
+}
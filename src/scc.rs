@@ -0,0 +1,155 @@
+use std::{collections::HashSet, fmt::Debug, hash::Hash};
+
+use crate::{
+    consts::{CONDENSED_SEPARATOR, MEMBERS_ATTR},
+    critical_path::critical_path,
+    graph::{Attrs, Edge, Graph},
+};
+
+pub(crate) fn scc<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<Vec<usize>> {
+    let n = graph.values.len();
+    let mut index = vec![usize::MAX; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack: Vec<usize> = vec![];
+    let mut next_index = 0;
+    let mut components = vec![];
+    for root in 0..n {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // (node, position in its deps)
+        let mut call_stack: Vec<(usize, usize)> = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(&mut (v, ref mut pos)) = call_stack.last_mut() {
+            let deps = graph.deps.get(&v).map_or(&[][..], |vs| vs.as_slice());
+            if let Some(&w) = deps.get(*pos) {
+                *pos += 1;
+                if index[w] == usize::MAX {
+                    index[w] = next_index;
+                    lowlink[w] = next_index;
+                    next_index += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    call_stack.push((w, 0));
+                } else if on_stack[w] {
+                    lowlink[v] = lowlink[v].min(index[w]);
+                }
+                continue;
+            }
+            call_stack.pop();
+            if let Some(&(u, _)) = call_stack.last() {
+                lowlink[u] = lowlink[u].min(lowlink[v]);
+            }
+            if lowlink[v] == index[v] {
+                let mut component = vec![];
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+        }
+    }
+    components.sort();
+    components
+}
+
+pub(crate) fn find_cycles<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<Vec<usize>> {
+    scc(graph)
+        .into_iter()
+        .filter(|c| c.len() > 1 || graph.deps.get(&c[0]).is_some_and(|vs| vs.contains(&c[0])))
+        .collect()
+}
+
+// the longest path of the condensation, each component represented by its smallest member
+pub(crate) fn longest_path<T: Clone + Debug + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<usize> {
+    let components = scc(graph);
+    let mut component_of = vec![0; graph.values.len()];
+    for (c, members) in components.iter().enumerate() {
+        for &i in members.iter() {
+            component_of[i] = c;
+        }
+    }
+    let mut condensed: Graph<usize> = Graph::default();
+    for c in 0..components.len() {
+        condensed.add_node(&c);
+    }
+    for e in graph.to_index_edges() {
+        let (from, to) = (component_of[e.0], component_of[e.1]);
+        if from != to {
+            condensed.add_edge(&Edge(from, to));
+        }
+    }
+    let result = critical_path(&condensed, &vec![1.0; components.len()]).expect("acyclic");
+    result.path.iter().map(|c| components[*c][0]).collect()
+}
+
+pub(crate) fn condense(graph: &Graph<String>) -> (Graph<String>, Vec<(String, Vec<String>)>) {
+    let components = scc(graph);
+    let mut component_of = vec![0; graph.values.len()];
+    let mut names = vec![];
+    let mut mapping = vec![];
+    // synthetic names get a numeric suffix when they clash with a node or another component
+    let mut taken: HashSet<String> = components
+        .iter()
+        .filter(|c| c.len() == 1)
+        .map(|c| graph.values[c[0]].clone())
+        .collect();
+    for (c, members) in components.iter().enumerate() {
+        for &i in members.iter() {
+            component_of[i] = c;
+        }
+        if members.len() == 1 {
+            names.push(graph.values[members[0]].clone());
+            continue;
+        }
+        let values: Vec<String> = members.iter().map(|i| graph.values[*i].clone()).collect();
+        let joined = values.join(CONDENSED_SEPARATOR);
+        let mut name = joined.clone();
+        for k in 2.. {
+            if taken.insert(name.clone()) {
+                break;
+            }
+            name = format!("{}#{}", joined, k);
+        }
+        names.push(name.clone());
+        mapping.push((name, values));
+    }
+    let mut condensed = Graph::default();
+    for (i, v) in graph.values.iter().enumerate() {
+        let members = &components[component_of[i]];
+        if members.len() == 1 {
+            let j = condensed.add_node(v);
+            condensed.add_node_attrs(j, graph.node_attrs(i).cloned().unwrap_or_default());
+        } else if members[0] == i {
+            let j = condensed.add_node(&names[component_of[i]]);
+            let values = members
+                .iter()
+                .map(|m| graph.values[*m].as_str())
+                .collect::<Vec<_>>();
+            condensed.add_node_attrs(
+                j,
+                Attrs::from([(MEMBERS_ATTR.to_owned(), values.join(" "))]),
+            );
+        }
+    }
+    for (e, attrs) in graph.to_index_edges_with_attrs() {
+        let (from, to) = (component_of[e.0], component_of[e.1]);
+        if from == to {
+            continue;
+        }
+        let edge = Edge(names[from].clone(), names[to].clone());
+        condensed.add_edge_with_attrs(&edge, attrs.clone());
+    }
+    condensed.dedup();
+    (condensed, mapping)
+}
@@ -0,0 +1,71 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::{
+    graph::Graph,
+    scc::{longest_path, scc},
+    wcc::wcc,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Stats {
+    pub(crate) nodes: usize,
+    pub(crate) edges: usize,
+    pub(crate) roots: usize,
+    pub(crate) leaves: usize,
+    pub(crate) max_in_degree: usize,
+    pub(crate) max_out_degree: usize,
+    pub(crate) avg_in_degree: f64,
+    pub(crate) avg_out_degree: f64,
+    pub(crate) top_fan_in: Vec<(usize, usize)>,
+    pub(crate) top_fan_out: Vec<(usize, usize)>,
+    pub(crate) longest_path: usize,
+    pub(crate) sccs: usize,
+    pub(crate) largest_scc: usize,
+    pub(crate) wccs: usize,
+}
+
+fn top(degrees: &[usize], n: usize) -> Vec<(usize, usize)> {
+    let mut ranked: Vec<(usize, usize)> = degrees
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, d)| *d > 0)
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked.truncate(n);
+    ranked
+}
+
+pub(crate) fn stats<T: Clone + Debug + Eq + Ord + Hash>(graph: &Graph<T>, n: usize) -> Stats {
+    let nodes = graph.values.len();
+    let mut in_degrees = vec![0; nodes];
+    let mut out_degrees = vec![0; nodes];
+    let edges = graph.to_index_edges();
+    for e in edges.iter() {
+        out_degrees[e.0] += 1;
+        in_degrees[e.1] += 1;
+    }
+    let avg = if nodes == 0 {
+        0.0
+    } else {
+        edges.len() as f64 / nodes as f64
+    };
+    let components = scc(graph);
+    Stats {
+        nodes,
+        edges: edges.len(),
+        roots: graph.find_roots().len(),
+        leaves: out_degrees.iter().filter(|d| **d == 0).count(),
+        max_in_degree: in_degrees.iter().copied().max().unwrap_or(0),
+        max_out_degree: out_degrees.iter().copied().max().unwrap_or(0),
+        avg_in_degree: avg,
+        avg_out_degree: avg,
+        top_fan_in: top(&in_degrees, n),
+        top_fan_out: top(&out_degrees, n),
+        // measured on the condensation, so each cycle counts as a single node
+        longest_path: longest_path(graph).len().saturating_sub(1),
+        sccs: components.len(),
+        largest_scc: components.iter().map(|c| c.len()).max().unwrap_or(0),
+        wccs: wcc(graph).len(),
+    }
+}
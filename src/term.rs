@@ -0,0 +1,201 @@
+use std::{collections::HashSet, fmt::Debug, hash::Hash};
+
+use crate::{feedback::feedback_arc_set, graph::Graph, tsort::tsort};
+
+const PALETTE: &[&str] = &["31", "32", "33", "34", "35", "36"];
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Charset {
+    node: &'static str,
+    vertical: &'static str,
+    horizontal: &'static str,
+    branch: &'static str,
+    fork: &'static str,
+    fork_end: &'static str,
+    merge: &'static str,
+    merge_end: &'static str,
+    cross: &'static str,
+}
+
+pub(crate) const UNICODE: Charset = Charset {
+    node: "●",
+    vertical: "│",
+    horizontal: "─",
+    branch: "├",
+    fork: "┬",
+    fork_end: "┐",
+    merge: "┴",
+    merge_end: "┘",
+    cross: "┼",
+};
+
+pub(crate) const ASCII: Charset = Charset {
+    node: "*",
+    vertical: "|",
+    horizontal: "-",
+    branch: "+",
+    fork: "+",
+    fork_end: "+",
+    merge: "+",
+    merge_end: "+",
+    cross: "+",
+};
+
+fn connector(
+    charset: &Charset,
+    columns: &[Option<usize>],
+    c: usize,
+    targets: &[usize],
+    fork: bool,
+) -> String {
+    let last = targets.iter().copied().max().unwrap_or(c);
+    let mut line = String::new();
+    for (k, column) in columns.iter().enumerate() {
+        let inside = c < k && k < last;
+        let glyph = if k == c {
+            charset.branch
+        } else if k == last {
+            if fork {
+                charset.fork_end
+            } else {
+                charset.merge_end
+            }
+        } else if targets.contains(&k) {
+            if fork {
+                charset.fork
+            } else {
+                charset.merge
+            }
+        } else if column.is_none() {
+            if inside {
+                charset.horizontal
+            } else {
+                " "
+            }
+        } else if inside {
+            charset.cross
+        } else {
+            charset.vertical
+        };
+        line.push_str(glyph);
+        if k + 1 < columns.len() {
+            line.push_str(if c <= k && k < last {
+                charset.horizontal
+            } else {
+                " "
+            });
+        }
+    }
+    line
+}
+
+fn vacant(columns: &mut Vec<Option<usize>>, after: usize) -> usize {
+    match (after..columns.len()).find(|k| columns[*k].is_none()) {
+        Some(k) => k,
+        None => {
+            columns.push(None);
+            columns.len() - 1
+        }
+    }
+}
+
+pub(crate) fn draw<T: Clone + Debug + Eq + Ord + Hash + ToString>(
+    graph: &Graph<T>,
+    charset: &Charset,
+    color: bool,
+) -> Vec<String> {
+    let back: HashSet<(usize, usize)> = feedback_arc_set(graph)
+        .into_iter()
+        .map(|e| (e.0, e.1))
+        .collect();
+    let acyclic = graph.filter_edges(|from, to, _| !back.contains(&(from, to)));
+    let mut order = vec![];
+    tsort(&acyclic, |n| order.push(n)).expect("acyclic");
+    let mut lines = vec![];
+    // each column holds the node its edge is heading to, or nothing
+    let mut columns: Vec<Option<usize>> = vec![];
+    // the column of the previous node if it had no children
+    let mut freed: Option<usize> = None;
+    for v in order {
+        let matches: Vec<usize> = (0..columns.len())
+            .filter(|k| columns[*k] == Some(v))
+            .collect();
+        let c = match matches.first() {
+            Some(&c) => c,
+            None => {
+                // don't continue right below a leaf, which would read as an edge from it
+                let c = (0..)
+                    .find(|k| Some(*k) != freed && !matches!(columns.get(*k), Some(Some(_))))
+                    .expect("vacant column");
+                if c >= columns.len() {
+                    columns.resize(c + 1, None);
+                }
+                columns[c] = Some(v);
+                c
+            }
+        };
+        if matches.len() > 1 {
+            lines.push(connector(charset, &columns, c, &matches[1..], false));
+            for &k in matches[1..].iter() {
+                columns[k] = None;
+            }
+            while columns.last() == Some(&None) {
+                columns.pop();
+            }
+        }
+        let mut row = columns
+            .iter()
+            .enumerate()
+            .map(|(k, column)| match (k == c, column, color) {
+                (true, _, true) => format!(
+                    "\x1b[{}m{}\x1b[0m",
+                    PALETTE[c % PALETTE.len()],
+                    charset.node
+                ),
+                (true, _, false) => charset.node.to_owned(),
+                (false, Some(_), _) => charset.vertical.to_owned(),
+                (false, None, _) => " ".to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        row.push(' ');
+        if color {
+            row.push_str(&format!("\x1b[1m{}\x1b[0m", graph.values[v].to_string()));
+        } else {
+            row.push_str(&graph.values[v].to_string());
+        }
+        let cycles: Vec<String> = graph
+            .deps
+            .get(&v)
+            .into_iter()
+            .flatten()
+            .filter(|t| back.contains(&(v, **t)))
+            .map(|t| graph.values[*t].to_string())
+            .collect();
+        if !cycles.is_empty() {
+            row.push_str(&format!(" (cycle to {})", cycles.join(", ")));
+        }
+        lines.push(row);
+        let mut children: Vec<usize> = vec![];
+        for &t in acyclic.deps.get(&v).into_iter().flatten() {
+            if !children.contains(&t) {
+                children.push(t);
+            }
+        }
+        columns[c] = children.first().copied();
+        freed = if children.is_empty() { Some(c) } else { None };
+        let mut targets = vec![];
+        for &t in children.iter().skip(1) {
+            let k = vacant(&mut columns, c + 1);
+            columns[k] = Some(t);
+            targets.push(k);
+        }
+        if !targets.is_empty() {
+            lines.push(connector(charset, &columns, c, &targets, true));
+        }
+        while columns.last() == Some(&None) {
+            columns.pop();
+        }
+    }
+    lines
+}
@@ -0,0 +1,83 @@
+use std::{collections::HashSet, hash::Hash};
+
+use crate::graph::Graph;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Prefix {
+    Indent,
+    Depth,
+    None,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Charset {
+    tee: &'static str,
+    ell: &'static str,
+    pipe: &'static str,
+    blank: &'static str,
+}
+
+pub(crate) const UTF8: Charset = Charset {
+    tee: "├── ",
+    ell: "└── ",
+    pipe: "│   ",
+    blank: "    ",
+};
+
+pub(crate) const ASCII: Charset = Charset {
+    tee: "|-- ",
+    ell: "`-- ",
+    pipe: "|   ",
+    blank: "    ",
+};
+
+// prints like `cargo tree`, marking subtrees already printed under the same root with (*)
+pub(crate) fn tree<T: Clone + Eq + Ord + Hash + ToString>(
+    graph: &Graph<T>,
+    roots: &[usize],
+    prefix: Prefix,
+    charset: &Charset,
+    max_depth: Option<usize>,
+) -> Vec<String> {
+    let mut lines = vec![];
+    for &root in roots.iter() {
+        let mut printed = HashSet::new();
+        // (node, depth, whether each ancestor below the root is the last child)
+        let mut stack: Vec<(usize, usize, Vec<bool>)> = vec![(root, 0, vec![])];
+        while let Some((n, depth, lasts)) = stack.pop() {
+            let mut line = match prefix {
+                Prefix::Indent => {
+                    let mut line = String::new();
+                    for (i, last) in lasts.iter().enumerate() {
+                        line.push_str(match (i + 1 == lasts.len(), last) {
+                            (true, true) => charset.ell,
+                            (true, false) => charset.tee,
+                            (false, true) => charset.blank,
+                            (false, false) => charset.pipe,
+                        });
+                    }
+                    line
+                }
+                Prefix::Depth => depth.to_string(),
+                Prefix::None => String::new(),
+            };
+            line.push_str(&graph.values[n].to_string());
+            let deps = graph.deps.get(&n).map_or(&[][..], |vs| vs.as_slice());
+            if !deps.is_empty() && !printed.insert(n) {
+                line.push_str(" (*)");
+                lines.push(line);
+                continue;
+            }
+            lines.push(line);
+            if max_depth.is_some_and(|max_depth| depth + 1 >= max_depth) {
+                continue;
+            }
+            for (i, &t) in deps.iter().enumerate().rev() {
+                let mut lasts = lasts.clone();
+                lasts.push(i + 1 == deps.len());
+                stack.push((t, depth + 1, lasts));
+            }
+        }
+    }
+    lines
+}
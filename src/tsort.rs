@@ -12,8 +12,7 @@ pub(crate) fn tsort<T: Clone + Debug + Eq + Ord + Hash, F: FnMut(usize)>(
     let mut stack = graph.find_roots();
     stack.reverse();
     stack.retain(|v| !rdeps.contains_key(v));
-    loop {
-        let Some(n) = stack.pop() else { break; };
+    while let Some(n) = stack.pop() {
         f(n);
         let Some(ts) = deps.remove(&n) else { continue; };
         for t in ts.iter().rev() {
@@ -0,0 +1,29 @@
+use std::hash::Hash;
+
+use crate::graph::Graph;
+
+fn find(parent: &mut [usize], mut n: usize) -> usize {
+    while parent[n] != n {
+        parent[n] = parent[parent[n]];
+        n = parent[n];
+    }
+    n
+}
+
+pub(crate) fn wcc<T: Clone + Eq + Ord + Hash>(graph: &Graph<T>) -> Vec<Vec<usize>> {
+    let n = graph.values.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+    for e in graph.to_index_edges() {
+        let (a, b) = (find(&mut parent, e.0), find(&mut parent, e.1));
+        if a != b {
+            parent[a.max(b)] = a.min(b);
+        }
+    }
+    let mut components: Vec<Vec<usize>> = vec![vec![]; n];
+    for i in 0..n {
+        let root = find(&mut parent, i);
+        components[root].push(i);
+    }
+    components.retain(|c| !c.is_empty());
+    components
+}
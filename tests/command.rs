@@ -98,6 +98,42 @@ fn test_dfs_tree() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_dfs_tree_prefix() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["dfs", "-T", "--prefix", "indent"],
+        include_str!("fixtures/dominators.txt"),
+        include_str!("fixtures/dominators.dfs.tree.txt")
+    );
+    test_filter!(
+        ["dfs", "--prefix", "indent", "--charset", "ascii"],
+        include_str!("fixtures/has_loop.txt"),
+        "a\n`-- b\n    `-- c\n        `-- d\n            `-- b (*)\n"
+    );
+    test_filter!(
+        ["dfs", "--prefix", "depth"],
+        include_str!("fixtures/example.txt"),
+        "0a\n1b\n2c\n3e\n2d\n2e\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dfs_tree_prefix_conflicts() -> Result<(), Box<dyn std::error::Error>> {
+    for args in [
+        ["dfs", "-T", "--charset", "ascii"],
+        ["dfs", "-P", "--prefix", "depth"],
+    ] {
+        let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+        let assert = cmd
+            .args(args)
+            .write_stdin(include_str!("fixtures/example.txt"))
+            .assert();
+        assert.failure().stdout("");
+    }
+    Ok(())
+}
+
 #[test]
 fn test_dfs_path() -> Result<(), Box<dyn std::error::Error>> {
     test_filter!(
@@ -168,3 +204,980 @@ fn test_tsort_has_loop() -> Result<(), Box<dyn std::error::Error>> {
     assert.failure();
     Ok(())
 }
+
+#[test]
+fn test_tsort_condense() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["tsort", "--condense"],
+        include_str!("fixtures/has_loop.txt"),
+        "a\nb+c+d\n",
+        "condensed: b+c+d b c d\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_condense() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "--condense", "-t", "json"],
+        include_str!("fixtures/has_loop.txt"),
+        r#"{"nodes":{"a":{},"b+c+d":{"members":"b c d"}},"edges":[{"from":"a","to":"b+c+d"}]}"#
+            .to_owned()
+            + "\n",
+        "condensed: b+c+d b c d\n"
+    );
+    test_filter!(
+        ["show", "--condense"],
+        "a b\nb a\nb a+b\n",
+        "a+b#2 a+b\n",
+        "condensed: a+b#2 a b\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_break_cycles() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["break-cycles"],
+        include_str!("fixtures/has_loop.txt"),
+        "d b\n"
+    );
+    test_filter!(
+        ["break-cycles", "-G"],
+        include_str!("fixtures/has_loop.txt"),
+        "a b\nb c\nc d\n",
+        "removed: d b\n"
+    );
+    test_filter!(
+        ["break-cycles", "-G", "-t", "json"],
+        include_str!("fixtures/has_loop.txt"),
+        "{\"a\":[\"b\"],\"b\":[\"c\"],\"c\":[\"d\"]}\n",
+        "removed: d b\n"
+    );
+    for args in [vec!["-t", "dot"], vec!["-I"], vec!["--condense"]] {
+        let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+        let assert = cmd
+            .arg("break-cycles")
+            .args(args)
+            .write_stdin(include_str!("fixtures/has_loop.txt"))
+            .assert();
+        assert.failure();
+    }
+    Ok(())
+}
+
+#[test]
+fn test_show_from_ninja() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "tests/fixtures/example.ninja"],
+        "",
+        include_str!("fixtures/example.ninja.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_from_ninja_explicit_inputs() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-f", "ninja", "--ninja-inputs", "explicit"],
+        include_str!("fixtures/example.ninja"),
+        include_str!("fixtures/example.ninja.explicit.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_from_ninja_include() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "tests/fixtures/ninja/build.ninja"],
+        "",
+        include_str!("fixtures/ninja.include.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_from_ninja_missing_include() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["show", "-f", "ninja"])
+        .write_stdin("include missing.ninja\n")
+        .assert();
+    assert.failure().stdout("");
+    Ok(())
+}
+
+#[test]
+fn test_show_from_go_mod_graph() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-f", "go-mod-graph"],
+        include_str!("fixtures/example.gomod.txt"),
+        include_str!("fixtures/example.gomod.show.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_from_go_mod_graph_stripped() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-f", "go-mod-graph", "--go-strip-versions"],
+        include_str!("fixtures/example.gomod.txt"),
+        include_str!("fixtures/example.gomod.stripped.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tsort_from_tsort() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["tsort", "-f", "tsort"],
+        include_str!("fixtures/example.tsort.in.txt"),
+        include_str!("fixtures/example.tsort.out.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tsort_from_tsort_odd_tokens() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["tsort", "-f", "tsort"])
+        .env("RUST_BACKTRACE", "0")
+        .write_stdin(include_str!("fixtures/odd_tokens.txt"))
+        .assert();
+    assert
+        .failure()
+        .stderr("Error: can't load tsort: input contains an odd number of tokens\n");
+    Ok(())
+}
+
+#[test]
+fn test_show_as_adjacency() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "adjacency"],
+        include_str!("fixtures/example.txt"),
+        include_str!("fixtures/example.adjacency.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_adjacency_with_adjacency() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-f", "adjacency", "-t", "adjacency"],
+        include_str!("fixtures/example.adjacency.in.txt"),
+        include_str!("fixtures/example.adjacency.out.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_csv_with_csv() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "csv", "tests/fixtures/example.csv"],
+        "",
+        include_str!("fixtures/example.csv")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_tsv_with_csv() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-f", "csv", "-t", "tsv"],
+        include_str!("fixtures/example.csv"),
+        include_str!("fixtures/example.tsv")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_csv_columns() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-f", "csv", "--csv-from", "to", "--csv-to", "from"],
+        "from,to\na,b\nb,c\nb,d\nb,e\nc,e\n",
+        include_str!("fixtures/example.inverted.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_json_with_attrs() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "json", "tests/fixtures/example.attrs.json"],
+        "",
+        include_str!("fixtures/example.attrs.json")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_dot_with_attrs() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "dot", "tests/fixtures/example.attrs.json"],
+        "",
+        include_str!("fixtures/example.attrs.dot")
+    );
+    test_filter!(
+        ["show", "-t", "dot", "-f", "json"],
+        r#"{"nodes":{"a":{"color":"red"},"b":{}},"edges":[{"from":"a","to":"b","attrs":{"kind":"dev","style":"bold","weight":1.5}}]}"#,
+        concat!(
+            "digraph {\n",
+            "    n0 [label=\"a\", depq_color=\"red\"];\n",
+            "    n1 [label=\"b\"];\n",
+            "\n",
+            "    n0 -> n1 [depq_kind=\"dev\", depq_style=\"bold\", depq_weight=\"1.5\", style=dashed];\n",
+            "}\n"
+        )
+    );
+    test_filter!(
+        ["show", "-t", "json", "-f", "json"],
+        r#"{"nodes":{"a":{},"b":{}},"edges":[{"from":"a","to":"b","attrs":{"optional":true,"weight":1.5}}]}"#,
+        "{\"nodes\":{\"a\":{},\"b\":{}},\"edges\":[{\"from\":\"a\",\"to\":\"b\",\"attrs\":{\"optional\":\"true\",\"weight\":\"1.5\"}}]}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_attr_filters() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        [
+            "show",
+            "--edge-attr",
+            "kind=normal",
+            "tests/fixtures/example.attrs.json"
+        ],
+        "",
+        "a b\tnormal\t1\nc e\tnormal\n"
+    );
+    test_filter!(
+        [
+            "show",
+            "--node-attr",
+            "version",
+            "-t",
+            "json",
+            "tests/fixtures/example.attrs.json"
+        ],
+        "",
+        "{\"nodes\":{\"a\":{\"version\":\"1.0\"}},\"edges\":[]}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_edge_kinds() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show"],
+        include_str!("fixtures/example.kinds.txt"),
+        include_str!("fixtures/example.kinds.txt")
+    );
+    test_filter!(
+        ["show", "--edge-kind", "normal,optional"],
+        include_str!("fixtures/example.kinds.txt"),
+        "a b\nb e\nc e\toptional\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_spaces_in_names() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "json"],
+        "a b c\nb c d\tdev\nc d\t\t2\n",
+        "{\"nodes\":{\"a\":{},\"b\":{},\"b c\":{},\"c\":{},\"c d\":{},\"d\":{}},\"edges\":[{\"from\":\"a\",\"to\":\"b c\"},{\"from\":\"b\",\"to\":\"c d\",\"attrs\":{\"kind\":\"dev\"}},{\"from\":\"c\",\"to\":\"d\",\"attrs\":{\"weight\":\"2\"}}]}\n"
+    );
+    test_filter!(
+        ["show"],
+        "a b c\nb c d\tdev\nc d\t\t2\n",
+        "a b c\nb c d\tdev\nc d\t\t2\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dfs_with_edge_kind() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["dfs", "-T", "-S", "a", "--edge-kind", "normal"],
+        include_str!("fixtures/example.kinds.txt"),
+        "* a\n    * b\n        * e\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tsort_excluding_edge_kinds() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["tsort", "--exclude-edge-kind", "dev,build"],
+        include_str!("fixtures/example.kinds.txt"),
+        include_str!("fixtures/example.tsort.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_dot_with_edge_kinds() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        [
+            "show",
+            "-t",
+            "dot",
+            "--dot-kind-style",
+            "optional=color=red"
+        ],
+        include_str!("fixtures/example.kinds.txt"),
+        include_str!("fixtures/example.kinds.dot")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_critical_path() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["critical-path", "-W", "tests/fixtures/example.weights.txt"],
+        include_str!("fixtures/example.txt"),
+        include_str!("fixtures/example.critical_path.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_critical_path_with_attrs() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["critical-path", "tests/fixtures/example.weights.json"],
+        "",
+        include_str!("fixtures/example.critical_path.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_critical_path_has_loop() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["critical-path"])
+        .write_stdin(include_str!("fixtures/has_loop.txt"))
+        .assert();
+    assert.failure();
+    Ok(())
+}
+
+#[test]
+fn test_shortest_path() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["shortest-path", "-S", "a", "-E", "e"],
+        include_str!("fixtures/example.weighted.txt"),
+        "path: a b c e\ncost: 6\n"
+    );
+    test_filter!(
+        ["shortest-path", "-S", "a", "-E", "e", "-t", "json"],
+        include_str!("fixtures/example.weighted.txt"),
+        "{\"cost\":6.0,\"path\":[\"a\",\"b\",\"c\",\"e\"]}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_shortest_path_unweighted() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["shortest-path", "-S", "a", "-E", "e"],
+        include_str!("fixtures/example.txt"),
+        "path: a b e\ncost: 2\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_shortest_path_parallel_edges() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["shortest-path", "-S", "a", "-E", "b"],
+        "a b\t\t3\na b\tdev\t5\n",
+        "path: a b\ncost: 3\n"
+    );
+    test_filter!(
+        ["shortest-path", "-S", "a", "-E", "b"],
+        "a b\tdev\t5\na b\n",
+        "path: a b\ncost: 1\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_shortest_path_no_path() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["shortest-path", "-S", "e", "-E", "a"])
+        .write_stdin(include_str!("fixtures/example.txt"))
+        .assert();
+    assert.failure();
+    Ok(())
+}
+
+#[test]
+fn test_diff() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "diff",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.new.txt",
+        ])
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/example.diff.txt"));
+    Ok(())
+}
+
+#[test]
+fn test_diff_as_dot() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "diff",
+            "-t",
+            "dot",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.new.txt",
+        ])
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/example.diff.dot"));
+    Ok(())
+}
+
+#[test]
+fn test_diff_same() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["diff", "-t", "json", "tests/fixtures/example.txt", "tests/fixtures/example.json"],
+        "",
+        "{\"added_edges\":[],\"added_nodes\":[],\"new_cycles\":[],\"new_transitive\":{},\"removed_edges\":[],\"removed_nodes\":[]}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_multiple_files() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        [
+            "show",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.json",
+            "-"
+        ],
+        include_str!("fixtures/example.other.txt"),
+        "a b\nb c\nb d\nb e\nc e\nx a\nx y\ny b\n",
+        "duplicate: a b\nduplicate: b c\nduplicate: b d\nduplicate: b e\nduplicate: c e\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_namespaces() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        [
+            "show",
+            "--namespace",
+            "--namespace-separator",
+            "::",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.other.txt"
+        ],
+        "",
+        "example::a example::b\nexample::b example::c\nexample::b example::d\nexample::b example::e\nexample::c example::e\nexample.other::x example.other::a\nexample.other::x example.other::y\nexample.other::y example.other::b\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_conflicting_files() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "show",
+            "--namespace",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.txt",
+        ])
+        .env("RUST_BACKTRACE", "0")
+        .assert();
+    assert.failure().stderr(
+        "Error: tests/fixtures/example.txt and tests/fixtures/example.txt have the same namespace: example\n",
+    );
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["show", "-", "-"])
+        .env("RUST_BACKTRACE", "0")
+        .write_stdin(include_str!("fixtures/example.txt"))
+        .assert();
+    assert
+        .failure()
+        .stderr("Error: stdin (-) is given more than once\n");
+    Ok(())
+}
+
+#[test]
+fn test_show_with_duplicates() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show"],
+        include_str!("fixtures/duplicates.txt"),
+        "a b\nb c\nb c\tdev\nc c\n",
+        "duplicate: a b\nself-loop: c\n"
+    );
+    test_filter!(
+        ["show", "--multigraph", "--on-self-loop", "ignore"],
+        include_str!("fixtures/duplicates.txt"),
+        "a b\na b\nb c\nb c\tdev\nc c\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_with_duplicates_across_files() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        [
+            "show",
+            "--multigraph",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.json"
+        ],
+        "",
+        "a b\na b\nb c\nb d\nb e\nb c\nb d\nb e\nc e\nc e\n"
+    );
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "show",
+            "--on-duplicate",
+            "error",
+            "tests/fixtures/example.txt",
+            "tests/fixtures/example.json",
+        ])
+        .assert();
+    assert.failure();
+    Ok(())
+}
+
+#[test]
+fn test_show_with_duplicates_as_error() -> Result<(), Box<dyn std::error::Error>> {
+    for arg in ["--on-duplicate", "--on-self-loop"] {
+        let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+        let assert = cmd
+            .args(["show", arg, "error"])
+            .write_stdin(include_str!("fixtures/duplicates.txt"))
+            .assert();
+        assert.failure();
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "check",
+            "-r",
+            "tests/fixtures/architecture.rules.json",
+            "tests/fixtures/architecture.txt",
+        ])
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/architecture.check.txt"));
+    Ok(())
+}
+
+#[test]
+fn test_check_as_junit() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "check",
+            "-t",
+            "junit",
+            "-r",
+            "tests/fixtures/architecture.rules.json",
+            "tests/fixtures/architecture.txt",
+        ])
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/architecture.check.xml"));
+    Ok(())
+}
+
+#[test]
+fn test_check_max_depth() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["check", "-r", "tests/fixtures/example.rules.json"])
+        .write_stdin(include_str!("fixtures/example.txt"))
+        .assert();
+    assert
+        .code(1)
+        .stdout("max-depth: depth 3 exceeds 2: a -> b -> c -> e\n");
+    test_filter!(
+        [
+            "check",
+            "-r",
+            "tests/fixtures/example.rules.json",
+            "--exclude-edge-kind",
+            "normal"
+        ],
+        include_str!("fixtures/example.txt"),
+        ""
+    );
+    Ok(())
+}
+
+#[test]
+fn test_check_max_depth_with_cycles() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args(["check", "-r", "tests/fixtures/example.rules.json"])
+        .write_stdin("a b\nb c\nc b\nc d\nd e\ne f\n")
+        .assert();
+    assert.code(1).stdout(concat!(
+        "no-cycles: contains a cycle: b -> c -> b\n",
+        "max-depth: depth 4 exceeds 2: a -> b -> d -> e -> f\n"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_check_as_sarif() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "check",
+            "-t",
+            "sarif",
+            "-r",
+            "tests/fixtures/architecture.rules.json",
+            "tests/fixtures/architecture.txt",
+        ])
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/architecture.check.sarif.json"));
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "check",
+            "-t",
+            "sarif",
+            "-r",
+            "tests/fixtures/example.rules.json",
+            "-",
+        ])
+        .write_stdin(include_str!("fixtures/example.txt"))
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/example.check.sarif.json"));
+    Ok(())
+}
+
+#[test]
+fn test_layers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "layers",
+            "-r",
+            "tests/fixtures/architecture.rules.json",
+            "tests/fixtures/architecture.txt",
+        ])
+        .assert();
+    assert.code(1).stdout(concat!(
+        "layers: domain must not depend on infrastructure: ui/app -> app/service -> domain/model -> infra/db\n",
+        "layers: application must not depend on infrastructure: ui/app -> app/service -> infra/db\n",
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_layers_as_dot() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let assert = cmd
+        .args([
+            "layers",
+            "-t",
+            "dot",
+            "-r",
+            "tests/fixtures/architecture.rules.json",
+            "tests/fixtures/architecture.txt",
+        ])
+        .assert();
+    assert
+        .code(1)
+        .stdout(include_str!("fixtures/architecture.layers.dot"));
+    Ok(())
+}
+
+#[test]
+fn test_stats() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["stats", "-n", "3"],
+        include_str!("fixtures/example.txt"),
+        include_str!("fixtures/example.stats.txt")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_stats_as_json() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        [
+            "stats",
+            "-t",
+            "json",
+            "-n",
+            "1",
+            "tests/fixtures/architecture.txt"
+        ],
+        "",
+        concat!(
+            r#"{"avg_in_degree":1.3333333333333333,"avg_out_degree":1.3333333333333333,"edges":8,"largest_scc":2,"#,
+            r#""leaves":1,"longest_path":3,"max_in_degree":2,"max_out_degree":2,"nodes":6,"roots":1,"sccs":5,"#,
+            r#""top_fan_in":[{"degree":2,"name":"db/conn"}],"top_fan_out":[{"degree":2,"name":"ui/app"}],"wccs":1}"#,
+            "\n"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rank() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["rank"],
+        include_str!("fixtures/example.txt"),
+        include_str!("fixtures/example.rank.txt")
+    );
+    test_filter!(
+        ["rank", "-s", "dependents", "-t", "csv", "-n", "2"],
+        include_str!("fixtures/example.txt"),
+        "name,pagerank,betweenness,dependents\ne,0.3234336839868892,0,3\nc,0.17482901836717324,0,2\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_dominators() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["dominators", "-S", "a"],
+        include_str!("fixtures/dominators.txt"),
+        "* a\n    * b\n        * g\n    * c\n    * d\n        * e\n    * f\n"
+    );
+    test_filter!(
+        ["dominators", "-t", "json", "-S", "b"],
+        include_str!("fixtures/dominators.txt"),
+        r#"{"b":null,"d":"b","e":"d","f":"b","g":"b"}"#.to_owned() + "\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_components() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["components"],
+        include_str!("fixtures/components.txt"),
+        "a b c d\nx y\n"
+    );
+    test_filter!(
+        ["components", "-A", "-t", "json"],
+        include_str!("fixtures/components.txt"),
+        concat!(
+            r#"{"nodes":{"a":{"component":"0"},"b":{"component":"0"},"c":{"component":"0"},"d":{"component":"0"},"#,
+            r#""x":{"component":"1"},"y":{"component":"1"}},"#,
+            r#""edges":[{"from":"a","to":"b"},{"from":"b","to":"c"},{"from":"x","to":"y"},{"from":"d","to":"c"}]}"#,
+            "\n"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_components_split() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let split = dir.path().join("component-{}.txt");
+    test_filter!(
+        ["components", "--split", split.to_str().unwrap()],
+        include_str!("fixtures/components.txt"),
+        ""
+    );
+    let read = |c: usize| std::fs::read_to_string(dir.path().join(format!("component-{}.txt", c)));
+    assert_eq!(read(0)?, "a b\nb c\nd c\n");
+    assert_eq!(read(1)?, "x y\n");
+    Ok(())
+}
+
+#[test]
+fn test_components_split_relative() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    cmd.current_dir(dir.path())
+        .args(["components", "--split", "component-{}.txt"])
+        .write_stdin(include_str!("fixtures/components.txt"))
+        .assert()
+        .success()
+        .stdout("");
+    let read = |c: usize| std::fs::read_to_string(dir.path().join(format!("component-{}.txt", c)));
+    assert_eq!(read(0)?, "a b\nb c\nd c\n");
+    assert_eq!(read(1)?, "x y\n");
+    Ok(())
+}
+
+#[test]
+fn test_show_as_html() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+    let output = cmd
+        .args(["show", "-t", "html"])
+        .write_stdin("a </script>\n<!--<script> &b\n")
+        .output()?;
+    assert!(output.status.success());
+    let html = String::from_utf8(output.stdout)?;
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains(concat!(
+        r#"const graph = {"edges":[[0,1],[2,3]],"nodes":[{"attrs":{},"name":"a"},"#,
+        r#"{"attrs":{},"name":"\u003c/script\u003e"},{"attrs":{},"name":"\u003c!--\u003cscript\u003e"},"#,
+        r#"{"attrs":{},"name":"\u0026b"}]};"#
+    )));
+    assert_eq!(html.matches("<script>").count(), 1);
+    assert_eq!(html.matches("</script>").count(), 1);
+    assert!(!html.contains("<!--"));
+    assert!(!html.contains("http://") && !html.contains("https://"));
+    Ok(())
+}
+
+#[test]
+fn test_show_as_svg() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "svg"],
+        include_str!("fixtures/example.txt"),
+        include_str!("fixtures/example.svg")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_svg_left_to_right() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "svg", "--dot-rankdir", "LR"],
+        include_str!("fixtures/example.txt"),
+        include_str!("fixtures/example.lr.svg")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_svg_with_cycle() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "svg"],
+        include_str!("fixtures/has_loop.txt"),
+        include_str!("fixtures/has_loop.svg")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_draw() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["draw"],
+        include_str!("fixtures/dominators.txt"),
+        include_str!("fixtures/dominators.draw.txt")
+    );
+    test_filter!(
+        ["draw", "--ascii"],
+        include_str!("fixtures/example.txt"),
+        "* a\n* b\n+-+-+\n* | | c\n+-+-+\n* | e\n  * d\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_draw_has_loop() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["draw", "--color", "never"],
+        include_str!("fixtures/has_loop.txt"),
+        "● a\n● b\n● c\n● d (cycle to b)\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_draw_disconnected() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["draw", "--color", "never"],
+        "a b\nc d\ne f\n",
+        "● a\n● b\n  ● c\n  ● d\n● e\n● f\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_dot_with_clusters() -> Result<(), Box<dyn std::error::Error>> {
+    test_filter!(
+        ["show", "-t", "dot", "--dot-cluster-separator", "/"],
+        include_str!("fixtures/hierarchy.txt"),
+        include_str!("fixtures/hierarchy.dot")
+    );
+    test_filter!(
+        [
+            "show",
+            "-t",
+            "dot",
+            "--dot-cluster-separator",
+            "/",
+            "--dot-cluster-depth",
+            "1",
+            "--dot-collapse"
+        ],
+        include_str!("fixtures/hierarchy.txt"),
+        include_str!("fixtures/hierarchy.collapsed.dot")
+    );
+    test_filter!(
+        [
+            "show",
+            "-t",
+            "dot",
+            "--dot-cluster-separator",
+            "/",
+            "--dot-cluster-depth",
+            "1",
+            "--dot-collapse"
+        ],
+        "services services/a\nmain services/a\tdev\nmain services/b\tdev\n",
+        concat!(
+            "digraph {\n",
+            "    n0 [label=\"services\"];\n",
+            "    n1 [label=\"services\", shape=\"folder\"];\n",
+            "    n2 [label=\"main\"];\n",
+            "\n",
+            "    n0 -> n1;\n",
+            "    n2 -> n1 [depq_kind=\"dev\", label=\"2\", style=dashed];\n",
+            "}\n"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_show_as_dot_with_bad_clusters() -> Result<(), Box<dyn std::error::Error>> {
+    for args in [
+        vec!["--dot-cluster-separator", ""],
+        vec!["--dot-cluster-separator", "/", "--dot-cluster-depth", "0"],
+        vec!["--dot-cluster-separator", "/", "--dot-collapse"],
+    ] {
+        let mut cmd = ::assert_cmd::Command::cargo_bin("depq")?;
+        let assert = cmd
+            .args(["show", "-t", "dot"])
+            .args(args)
+            .write_stdin(include_str!("fixtures/hierarchy.txt"))
+            .assert();
+        assert.failure();
+    }
+    Ok(())
+}